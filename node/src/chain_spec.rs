@@ -1,18 +1,76 @@
 use loot_node_runtime::{
-    AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, Signature, SudoConfig,
-    SystemConfig, WASM_BINARY,
+    opaque::SessionKeys,
+    royalty_nft::{GenesisClass, GenesisToken},
+    AccountId, AuraConfig, Balance, BalancesConfig, ClassData, CouncilConfig, DemocracyConfig,
+    GenesisConfig, GrandpaConfig, IndicesConfig, LootNFTConfig, OrmlNFTConfig, Perbill, Rarity,
+    SessionConfig, Signature, StakerStatus, StakingConfig, SudoConfig, SystemConfig,
+    TechnicalCommitteeConfig, TokenData, DOLLARS, DOTS, WASM_BINARY,
 };
-use sc_service::ChainType;
+use sc_chain_spec::ChainSpecExtension;
+use sc_network::config::MultiaddrWithPeerId;
+use sc_service::{ChainType, Properties};
+use sc_telemetry::TelemetryEndpoints;
+use serde::{Deserialize, Serialize};
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{sr25519, Pair, Public};
 use sp_finality_grandpa::AuthorityId as GrandpaId;
 use sp_runtime::traits::{IdentifyAccount, Verify};
+use std::{fs, path::PathBuf};
 
-// The URL for the telemetry server.
-// const STAGING_TELEMETRY_URL: &str = "wss://telemetry.polkadot.io/submit/";
+/// Symbol of the `DOTS` unit, as shown by wallets.
+pub const TOKEN_SYMBOL: &str = "LOOT";
+/// SS58 address format of Loot networks, the generic Substrate one until a prefix is registered.
+pub const SS58_FORMAT: u8 = 42;
+
+/// Node settings carried by the chain spec, so each network can be tuned without rebuilding the
+/// node. Fields missing from a chain spec take their default values.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ChainSpecExtension)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Extensions {
+    pub grandpa: GrandpaSettings,
+    pub aura: AuraSettings,
+}
+
+impl Extensions {
+    /// The extensions of `chain_spec`, or the defaults if it was loaded without them.
+    pub fn from_chain_spec(chain_spec: &dyn sc_service::ChainSpec) -> Self {
+        sc_chain_spec::get_extension::<Self>(chain_spec.extensions())
+            .cloned()
+            .unwrap_or_default()
+    }
+}
+
+/// Settings of the GRANDPA voter.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct GrandpaSettings {
+    /// Interval between gossip rounds, in milliseconds.
+    pub gossip_duration: u64,
+    /// Number of blocks between the justifications that are stored and served to light clients.
+    pub justification_period: u32,
+}
+
+impl Default for GrandpaSettings {
+    fn default() -> Self {
+        GrandpaSettings {
+            gossip_duration: 333,
+            justification_period: 512,
+        }
+    }
+}
+
+/// Settings of Aura block authoring. The slot duration is set by the runtime, as every node must
+/// agree on it.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AuraSettings {
+    /// Author blocks even when the node has no peers or is syncing, e.g. for a single validator
+    /// testnet. `--force-authoring` enables it as well.
+    pub force_authoring: bool,
+}
 
 /// Specialized `ChainSpec`. This is a specialization of the general Substrate ChainSpec type.
-pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig>;
+pub type ChainSpec = sc_service::GenericChainSpec<GenesisConfig, Extensions>;
 
 /// Generate a crypto pair from seed.
 pub fn get_from_seed<TPublic: Public>(seed: &str) -> <TPublic::Pair as Pair>::Public {
@@ -31,9 +89,87 @@ where
     AccountPublic::from(get_from_seed::<TPublic>(seed)).into_account()
 }
 
-/// Generate an Aura authority key.
-pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
-    (get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
+/// Amount each initial validator bonds.
+const STASH: Balance = 10_000 * DOTS;
+
+/// Generate the stash and controller accounts and the session keys of a validator.
+pub fn authority_keys_from_seed(s: &str) -> (AccountId, AccountId, AuraId, GrandpaId) {
+    (
+        get_account_id_from_seed::<sr25519::Public>(&format!("{}//stash", s)),
+        get_account_id_from_seed::<sr25519::Public>(s),
+        get_from_seed::<AuraId>(s),
+        get_from_seed::<GrandpaId>(s),
+    )
+}
+
+fn session_keys(aura: AuraId, grandpa: GrandpaId) -> SessionKeys {
+    SessionKeys { aura, grandpa }
+}
+
+/// A marketplace class created at genesis.
+pub type NftClass = GenesisClass<AccountId, ClassData, TokenData, Balance>;
+
+fn demo_token(
+    owner: &AccountId,
+    name: &str,
+    rarity: Rarity,
+    sale: Option<Balance>,
+) -> GenesisToken<AccountId, TokenData, Balance> {
+    GenesisToken {
+        owner: owner.clone(),
+        metadata: name.as_bytes().to_vec(),
+        // the royalty is filled in from the class when the token is minted
+        data: TokenData { rarity, royalty: 0 },
+        sale,
+    }
+}
+
+/// A few collections owned by Alice and Bob, some of whose items are for sale, so wallets and
+/// the marketplace have something to show on a fresh network.
+fn demo_collections() -> Vec<NftClass> {
+    let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+    let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+    let charlie = get_account_id_from_seed::<sr25519::Public>("Charlie");
+
+    vec![
+        GenesisClass {
+            owner: alice.clone(),
+            metadata: b"Loot Swords".to_vec(),
+            data: ClassData { category: 0, creator_verified: false },
+            mint_price: 10 * DOLLARS,
+            royalty: 10,
+            soulbound: false,
+            tokens: vec![
+                demo_token(&alice, "Rusty Sword", Rarity::Common, Some(5 * DOLLARS)),
+                demo_token(&alice, "Flame Blade", Rarity::Rare, Some(50 * DOLLARS)),
+                demo_token(&bob, "Excalibur", Rarity::Legendary, None),
+            ],
+        },
+        GenesisClass {
+            owner: bob.clone(),
+            metadata: b"Loot Shields".to_vec(),
+            data: ClassData { category: 1, creator_verified: false },
+            mint_price: 5 * DOLLARS,
+            royalty: 5,
+            soulbound: false,
+            tokens: vec![
+                demo_token(&bob, "Wooden Shield", Rarity::Common, Some(2 * DOLLARS)),
+                demo_token(&charlie, "Tower Shield", Rarity::Epic, Some(80 * DOLLARS)),
+            ],
+        },
+        GenesisClass {
+            owner: alice,
+            metadata: b"Loot Founders".to_vec(),
+            data: ClassData { category: 2, creator_verified: false },
+            mint_price: 0,
+            royalty: 0,
+            soulbound: true,
+            tokens: vec![
+                demo_token(&bob, "Founder Badge", Rarity::Uncommon, None),
+                demo_token(&charlie, "Founder Badge", Rarity::Uncommon, None),
+            ],
+        },
+    ]
 }
 
 pub fn development_config() -> Result<ChainSpec, String> {
@@ -48,10 +184,12 @@ pub fn development_config() -> Result<ChainSpec, String> {
         move || {
             testnet_genesis(
                 wasm_binary,
-                // Initial PoA authorities
+                // Initial validators
                 vec![authority_keys_from_seed("Alice")],
                 // Sudo account
                 get_account_id_from_seed::<sr25519::Public>("Alice"),
+                // Council and technical committee
+                vec![get_account_id_from_seed::<sr25519::Public>("Alice")],
                 // Pre-funded accounts
                 vec![
                     get_account_id_from_seed::<sr25519::Public>("Alice"),
@@ -59,6 +197,8 @@ pub fn development_config() -> Result<ChainSpec, String> {
                     get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
                     get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
                 ],
+                // Marketplace classes
+                vec![],
                 true,
             )
         },
@@ -71,29 +211,44 @@ pub fn development_config() -> Result<ChainSpec, String> {
         // Properties
         None,
         // Extensions
-        None,
+        Default::default(),
     ))
 }
 
 pub fn local_testnet_config() -> Result<ChainSpec, String> {
+    local_testnet("Local Testnet", "local_testnet", vec![])
+}
+
+/// The local testnet with `demo_collections` on the marketplace.
+pub fn local_testnet_demo_config() -> Result<ChainSpec, String> {
+    local_testnet("Local Testnet Demo", "local_testnet_demo", demo_collections())
+}
+
+fn local_testnet(name: &str, id: &str, nft_classes: Vec<NftClass>) -> Result<ChainSpec, String> {
     let wasm_binary = WASM_BINARY.ok_or("Development wasm binary not available".to_string())?;
 
     Ok(ChainSpec::from_genesis(
         // Name
-        "Local Testnet",
+        name,
         // ID
-        "local_testnet",
+        id,
         ChainType::Local,
         move || {
             testnet_genesis(
                 wasm_binary,
-                // Initial PoA authorities
+                // Initial validators
                 vec![
                     authority_keys_from_seed("Alice"),
                     authority_keys_from_seed("Bob"),
                 ],
                 // Sudo account
                 get_account_id_from_seed::<sr25519::Public>("Alice"),
+                // Council and technical committee
+                vec![
+                    get_account_id_from_seed::<sr25519::Public>("Alice"),
+                    get_account_id_from_seed::<sr25519::Public>("Bob"),
+                    get_account_id_from_seed::<sr25519::Public>("Charlie"),
+                ],
                 // Pre-funded accounts
                 vec![
                     get_account_id_from_seed::<sr25519::Public>("Alice"),
@@ -109,6 +264,8 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
                     get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
                     get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
                 ],
+                // Marketplace classes
+                nft_classes.clone(),
                 true,
             )
         },
@@ -121,16 +278,160 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
         // Properties
         None,
         // Extensions
-        None,
+        Default::default(),
+    ))
+}
+
+/// Number of decimals of the `DOTS` unit, i.e. its power of ten.
+pub fn token_decimals() -> u32 {
+    let mut decimals = 0;
+    let mut unit = DOTS;
+    while unit > 1 {
+        unit /= 10;
+        decimals += 1;
+    }
+    decimals
+}
+
+/// The properties wallets read to display balances and addresses.
+fn properties() -> Properties {
+    let mut properties = Properties::new();
+    properties.insert("tokenSymbol".into(), TOKEN_SYMBOL.into());
+    properties.insert("tokenDecimals".into(), token_decimals().into());
+    properties.insert("ss58Format".into(), SS58_FORMAT.into());
+    properties
+}
+
+/// A network run with real keys, as described by the templates in `node/res`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct NetworkConfig {
+    name: String,
+    id: String,
+    protocol_id: String,
+    boot_nodes: Vec<MultiaddrWithPeerId>,
+    /// Telemetry URLs or multiaddresses, with the verbosity sent to each.
+    telemetry_endpoints: Vec<(String, u8)>,
+    /// Validators at genesis.
+    authorities: Vec<AuthorityKeys>,
+    sudo: Option<AccountId>,
+    /// Members of the council and technical committee.
+    council: Vec<AccountId>,
+    /// Endowed accounts, which must include the stashes of `authorities`.
+    balances: Vec<(AccountId, Balance)>,
+}
+
+impl NetworkConfig {
+    /// Whether there are authorities and every stash can pay the bond staked at genesis, which
+    /// would otherwise panic while building the genesis state.
+    fn check_authorities(&self, file: &str) -> Result<(), String> {
+        if self.authorities.is_empty() {
+            return Err(format!("{} lists no authorities", file));
+        }
+
+        for keys in &self.authorities {
+            let endowed = self
+                .balances
+                .iter()
+                .find(|(account, _)| *account == keys.stash)
+                .map_or(0, |(_, balance)| *balance);
+            if endowed < STASH {
+                return Err(format!(
+                    "{}: stash {} is endowed with {}, less than its bond of {}",
+                    file, keys.stash, endowed, STASH
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The public keys of a validator, as SS58 addresses.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct AuthorityKeys {
+    stash: AccountId,
+    controller: AccountId,
+    aura: AuraId,
+    grandpa: GrandpaId,
+}
+
+fn network_config(file: &str, json: &[u8]) -> Result<ChainSpec, String> {
+    let wasm_binary = WASM_BINARY.ok_or("Wasm binary not available".to_string())?;
+    let config: NetworkConfig =
+        serde_json::from_slice(json).map_err(|e| format!("Invalid {}: {}", file, e))?;
+
+    config.check_authorities(file)?;
+    let sudo = config
+        .sudo
+        .ok_or_else(|| format!("{} sets no sudo account", file))?;
+    let telemetry = TelemetryEndpoints::new(config.telemetry_endpoints)
+        .map_err(|e| format!("Invalid telemetry endpoint in {}: {}", file, e))?;
+    let authorities: Vec<_> = config
+        .authorities
+        .into_iter()
+        .map(|keys| (keys.stash, keys.controller, keys.aura, keys.grandpa))
+        .collect();
+    let council = config.council;
+    let balances = config.balances;
+
+    Ok(ChainSpec::from_genesis(
+        // Name
+        &config.name,
+        // ID
+        &config.id,
+        ChainType::Live,
+        move || GenesisConfig {
+            pallet_balances: Some(BalancesConfig {
+                balances: balances.clone(),
+            }),
+            ..testnet_genesis(
+                wasm_binary,
+                authorities.clone(),
+                sudo.clone(),
+                council.clone(),
+                vec![],
+                vec![],
+                false,
+            )
+        },
+        // Bootnodes
+        config.boot_nodes,
+        // Telemetry
+        Some(telemetry),
+        // Protocol ID
+        Some(&config.protocol_id),
+        // Properties
+        Some(properties()),
+        // Extensions
+        Default::default(),
     ))
 }
 
+/// A chain spec read from `path`, which holds either a chain spec or a network configuration
+/// like the templates in `node/res`. Network configurations have no `genesis`.
+pub fn from_json_file(path: PathBuf) -> Result<ChainSpec, String> {
+    let json = fs::read(&path)
+        .map_err(|e| format!("Error opening spec file {}: {}", path.display(), e))?;
+    let is_network_config = serde_json::from_slice::<serde_json::Value>(&json)
+        .map_or(false, |spec| spec.get("genesis").is_none());
+
+    if is_network_config {
+        network_config(&path.display().to_string(), &json)
+    } else {
+        ChainSpec::from_json_bytes(json)
+    }
+}
+
 /// Configure initial storage state for FRAME modules.
 fn testnet_genesis(
     wasm_binary: &[u8],
-    initial_authorities: Vec<(AuraId, GrandpaId)>,
+    initial_authorities: Vec<(AccountId, AccountId, AuraId, GrandpaId)>,
     root_key: AccountId,
+    council: Vec<AccountId>,
     endowed_accounts: Vec<AccountId>,
+    nft_classes: Vec<NftClass>,
     _enable_println: bool,
 ) -> GenesisConfig {
     GenesisConfig {
@@ -147,18 +448,97 @@ fn testnet_genesis(
                 .map(|k| (k, 1 << 60))
                 .collect(),
         }),
+        // the authorities are set by `pallet_session` from the validators' keys
         pallet_aura: Some(AuraConfig {
-            authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect(),
+            authorities: vec![],
         }),
         pallet_grandpa: Some(GrandpaConfig {
-            authorities: initial_authorities
-                .iter()
-                .map(|x| (x.1.clone(), 1))
-                .collect(),
+            authorities: vec![],
         }),
         pallet_sudo: Some(SudoConfig {
             // Assign network admin rights.
             key: root_key,
         }),
+        royalty_nft: Some(LootNFTConfig {
+            classes: nft_classes,
+        }),
+        orml_nft: Some(OrmlNFTConfig { tokens: vec![] }),
+        pallet_democracy: Some(DemocracyConfig::default()),
+        pallet_collective_Instance1: Some(CouncilConfig {
+            members: council.clone(),
+            phantom: Default::default(),
+        }),
+        pallet_collective_Instance2: Some(TechnicalCommitteeConfig {
+            members: council,
+            phantom: Default::default(),
+        }),
+        pallet_treasury: Some(Default::default()),
+        pallet_indices: Some(IndicesConfig { indices: vec![] }),
+        pallet_staking: Some(StakingConfig {
+            validator_count: initial_authorities.len() as u32 * 2,
+            minimum_validator_count: initial_authorities.len() as u32,
+            stakers: initial_authorities
+                .iter()
+                .map(|x| (x.0.clone(), x.1.clone(), STASH, StakerStatus::Validator))
+                .collect(),
+            invulnerables: initial_authorities.iter().map(|x| x.0.clone()).collect(),
+            slash_reward_fraction: Perbill::from_percent(10),
+            ..Default::default()
+        }),
+        pallet_session: Some(SessionConfig {
+            keys: initial_authorities
+                .iter()
+                .map(|x| (x.0.clone(), x.0.clone(), session_keys(x.2.clone(), x.3.clone())))
+                .collect(),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_decimals_should_match_dots() {
+        assert_eq!(token_decimals(), 12);
+        assert_eq!(10u128.pow(token_decimals()), DOTS);
+    }
+
+    #[test]
+    fn network_configs_should_parse() {
+        for json in &[
+            &include_bytes!("../res/staging.json")[..],
+            &include_bytes!("../res/loot-mainnet.json")[..],
+        ] {
+            serde_json::from_slice::<NetworkConfig>(json).unwrap();
+        }
+    }
+
+    #[test]
+    fn network_stashes_should_be_endowed() {
+        let (stash, controller, aura, grandpa) = authority_keys_from_seed("Alice");
+        let mut config = NetworkConfig {
+            name: "Test".into(),
+            id: "test".into(),
+            protocol_id: "test".into(),
+            boot_nodes: vec![],
+            telemetry_endpoints: vec![],
+            authorities: vec![],
+            sudo: None,
+            council: vec![],
+            balances: vec![(stash.clone(), STASH - 1)],
+        };
+        assert!(config.check_authorities("test.json").is_err());
+
+        config.authorities.push(AuthorityKeys {
+            stash: stash.clone(),
+            controller,
+            aura,
+            grandpa,
+        });
+        assert!(config.check_authorities("test.json").is_err());
+
+        config.balances = vec![(stash, STASH)];
+        assert_eq!(config.check_authorities("test.json"), Ok(()));
     }
 }
@@ -1,4 +1,6 @@
+use crate::chain_spec::Extensions;
 use sc_cli::RunCmd;
+use std::str::FromStr;
 use structopt::StructOpt;
 
 #[derive(Debug, StructOpt)]
@@ -8,6 +10,73 @@ pub struct Cli {
 
     #[structopt(flatten)]
     pub run: RunCmd,
+
+    #[structopt(flatten)]
+    pub consensus: ConsensusParams,
+}
+
+/// Overrides of the consensus settings in the chain spec extensions.
+#[derive(Debug, Clone, StructOpt)]
+pub struct ConsensusParams {
+    /// Interval between GRANDPA gossip rounds in milliseconds, instead of the chain spec's.
+    #[structopt(long, value_name = "MILLISECONDS")]
+    pub grandpa_gossip_duration: Option<u64>,
+
+    /// Number of blocks between stored GRANDPA justifications, instead of the chain spec's.
+    #[structopt(long, value_name = "BLOCKS")]
+    pub grandpa_justification_period: Option<u32>,
+
+    /// Seal blocks without Aura and GRANDPA, for development and tests: `instant` seals a block
+    /// for every transaction, `manual` only through the `engine_createBlock` RPC and
+    /// `interval=N` every N milliseconds. Sealed blocks can be finalized with
+    /// `engine_finalizeBlock`.
+    #[structopt(long, value_name = "MODE")]
+    pub sealing: Option<Sealing>,
+}
+
+/// When a node run with `--sealing` seals blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sealing {
+    /// As soon as a transaction enters the pool.
+    Instant,
+    /// Only when asked to over RPC.
+    Manual,
+    /// Every given number of milliseconds, even if the block would be empty.
+    Interval(u64),
+}
+
+impl FromStr for Sealing {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "instant" => Ok(Sealing::Instant),
+            "manual" => Ok(Sealing::Manual),
+            _ => s
+                .strip_prefix("interval=")
+                .and_then(|millis| millis.parse().ok())
+                .filter(|millis| *millis > 0)
+                .map(Sealing::Interval)
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid sealing mode `{}`, expected `instant`, `manual` or `interval=N`",
+                        s
+                    )
+                }),
+        }
+    }
+}
+
+impl ConsensusParams {
+    /// Apply the overrides given on the command line to `extensions`.
+    pub fn apply(&self, extensions: &mut Extensions) {
+        if let Some(gossip_duration) = self.grandpa_gossip_duration {
+            extensions.grandpa.gossip_duration = gossip_duration;
+        }
+        if let Some(justification_period) = self.grandpa_justification_period {
+            extensions.grandpa.justification_period = justification_period;
+        }
+    }
 }
 
 #[derive(Debug, StructOpt)]
@@ -33,7 +102,25 @@ pub enum Subcommand {
     /// Revert the chain to a previous state.
     Revert(sc_cli::RevertCmd),
 
+    /// Export the polkadot.js type definitions of the runtime.
+    ExportTypes(crate::export_types::ExportTypesCmd),
+
     /// The custom benchmark subcommmand benchmarking runtime pallets.
     #[structopt(name = "benchmark", about = "Benchmark runtime pallets.")]
     Benchmark(frame_benchmarking_cli::BenchmarkCmd),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sealing_should_parse() {
+        assert_eq!("instant".parse(), Ok(Sealing::Instant));
+        assert_eq!("manual".parse(), Ok(Sealing::Manual));
+        assert_eq!("interval=500".parse(), Ok(Sealing::Interval(500)));
+        assert!("interval=0".parse::<Sealing>().is_err());
+        assert!("interval=".parse::<Sealing>().is_err());
+        assert!("aura".parse::<Sealing>().is_err());
+    }
+}
@@ -50,9 +50,8 @@ impl SubstrateCli for Cli {
         Ok(match id {
             "dev" => Box::new(chain_spec::development_config()?),
             "" | "local" => Box::new(chain_spec::local_testnet_config()?),
-            path => Box::new(chain_spec::ChainSpec::from_json_file(
-                std::path::PathBuf::from(path),
-            )?),
+            "local-demo" => Box::new(chain_spec::local_testnet_demo_config()?),
+            path => Box::new(chain_spec::from_json_file(std::path::PathBuf::from(path))?),
         })
     }
 
@@ -132,6 +131,7 @@ pub fn run() -> sc_cli::Result<()> {
                 Ok((cmd.run(client, backend), task_manager))
             })
         }
+        Some(Subcommand::ExportTypes(cmd)) => cmd.run(),
         Some(Subcommand::Benchmark(cmd)) => {
             if cfg!(feature = "runtime-benchmarks") {
                 let runner = cli.create_runner(cmd)?;
@@ -147,7 +147,11 @@ pub fn run() -> sc_cli::Result<()> {
             let runner = cli.create_runner(&cli.run)?;
             runner.run_node_until_exit(|config| match config.role {
                 Role::Light => service::new_light(config),
-                _ => service::new_full(config),
+                _ => {
+                    let mut extensions = chain_spec::Extensions::from_chain_spec(&*config.chain_spec);
+                    cli.consensus.apply(&mut extensions);
+                    service::new_full(config, extensions, cli.consensus.sealing)
+                }
             })
         }
     }
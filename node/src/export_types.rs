@@ -0,0 +1,256 @@
+//! The `export-types` subcommand: writes the polkadot.js type definitions of the runtime, after
+//! checking them against the runtime metadata.
+
+use codec::{Decode, Encode};
+use frame_metadata::{DecodeDifferent, RuntimeMetadata, RuntimeMetadataPrefixed, StorageEntryType};
+use loot_node_runtime::{type_definitions::type_definitions, Runtime};
+use serde_json::{Map, Value};
+use std::{collections::BTreeSet, fs, path::PathBuf};
+use structopt::StructOpt;
+
+/// Types polkadot.js defines itself, which need no custom definition.
+const BUILTIN_TYPES: &[&str] = &[
+    // primitives and containers
+    "bool", "u8", "u16", "u32", "u64", "u128", "i8", "i16", "i32", "i64", "i128", "Null", "Text",
+    "Bytes", "Vec", "Option", "Result", "Compact", "Box", "BTreeMap", "BTreeSet", "H160", "H256",
+    // frame_system
+    "AccountData", "AccountId", "AccountInfo", "BlockNumber", "Call", "ChangesTrieConfiguration",
+    "DigestOf", "DispatchError", "DispatchInfo", "DispatchResult", "Event", "EventIndex",
+    "EventRecord", "ExtrinsicsWeight", "Hash", "Index", "Key", "KeyValue",
+    "LastRuntimeUpgradeInfo", "ModuleId", "Perbill", "Permill", "Phase", "RefCount",
+    "RuntimeDbWeight", "Weight",
+    // pallet_balances and pallet_transaction_payment
+    "Balance", "BalanceLock", "BalanceOf", "Multiplier", "Status", "WeightToFeeCoefficient",
+    // pallet_timestamp, pallet_aura and pallet_grandpa
+    "AuthorityId", "AuthorityList", "EquivocationProof", "KeyOwnerProof", "Moment",
+    "SessionIndex", "SetId", "StoredPendingChange", "StoredState",
+    // pallet_identity and pallet_indices
+    "Data", "AccountIndex",
+    // pallet_collective, pallet_democracy and pallet_scheduler
+    "AccountVote", "CollectiveOrigin", "Conviction", "MemberCount", "Period", "PreimageStatus",
+    "Priority", "PropIndex", "Proposal", "ProposalIndex", "ReferendumIndex", "ReferendumInfo",
+    "Scheduled", "SystemOrigin", "TaskAddress", "VoteThreshold", "Votes", "Voting",
+    // pallet_treasury
+    "Bounty", "BountyIndex", "OpenTip", "Percent",
+    // pallet_utility, pallet_proxy and pallet_multisig
+    "CallHash", "CallHashOf", "ProxyAnnouncement", "ProxyDefinition", "Multisig", "OpaqueCall",
+    "Timepoint",
+    // pallet_authorship, pallet_session and pallet_staking
+    "ActiveEraInfo", "CompactAssignments", "ElectionCompute", "ElectionResult", "ElectionScore",
+    "ElectionSize", "ElectionStatus", "EraIndex", "EraRewardPoints", "Exposure", "Forcing",
+    "KeyTypeId", "Nominations", "RewardDestination", "SessionKeys2", "SlashingSpans", "SpanIndex",
+    "SpanRecord", "StakingLedger", "UnappliedSlash", "UncleEntryItem", "ValidatorId",
+    "ValidatorIndex", "ValidatorPrefs",
+    // pallet_offences and the historical sessions of pallet_session
+    "DeferredOffenceOf", "IdentificationTuple", "Kind", "OffenceDetails", "OpaqueTimeSlot",
+    "ReportIdOf",
+];
+
+/// Types polkadot.js defines itself under a name other pallets reuse for their own types, with
+/// the modules they are built in for.
+const BUILTIN_MODULE_TYPES: &[(&str, &str)] = &[("Balances", "Releases"), ("Staking", "Releases")];
+
+#[derive(Debug, StructOpt)]
+pub struct ExportTypesCmd {
+    /// Write the type definitions to this file rather than to stdout.
+    #[structopt(short, long, parse(from_os_str))]
+    pub output: Option<PathBuf>,
+}
+
+impl ExportTypesCmd {
+    /// Check the type definitions cover the runtime metadata and write them out.
+    pub fn run(&self) -> sc_cli::Result<()> {
+        let types = type_definitions();
+        let missing = missing_types(&types)?;
+        if !missing.is_empty() {
+            return Err(format!(
+                "No type definition for {}. Add them to `runtime/src/type_definitions.rs`, or to \
+                the built-in types of `export-types` if polkadot.js already knows them.",
+                missing.join(", ")
+            )
+            .into());
+        }
+
+        let json = serde_json::to_string_pretty(&types).map_err(|e| e.to_string())? + "\n";
+        match &self.output {
+            Some(path) => fs::write(path, json)?,
+            None => print!("{}", json),
+        }
+
+        Ok(())
+    }
+}
+
+/// Types used by the runtime metadata or by `types` that have no definition.
+fn missing_types(types: &Value) -> Result<Vec<String>, String> {
+    let defined = types
+        .as_object()
+        .ok_or("Type definitions must be a JSON object")?;
+
+    let mut used = metadata_types()?;
+    let mut definitions_use = BTreeSet::new();
+    defined
+        .values()
+        .for_each(|definition| definition_types(definition, &mut definitions_use));
+    // definitions aren't tied to a module, so only the types built in everywhere cover them
+    used.extend(definitions_use.into_iter().map(|ty| (String::new(), ty)));
+
+    Ok(undefined(used, defined))
+}
+
+/// The types of `used`, each given with the module using it, that are neither built in nor
+/// `defined`.
+fn undefined(used: BTreeSet<(String, String)>, defined: &Map<String, Value>) -> Vec<String> {
+    let missing: BTreeSet<_> = used
+        .into_iter()
+        .filter(|(module, ty)| {
+            !BUILTIN_TYPES.contains(&ty.as_str())
+                && !BUILTIN_MODULE_TYPES.contains(&(module.as_str(), ty.as_str()))
+                && !defined.contains_key(ty)
+        })
+        .map(|(_, ty)| ty)
+        .collect();
+    missing.into_iter().collect()
+}
+
+/// The value of metadata that was decoded rather than built natively.
+fn decoded<B, O>(value: DecodeDifferent<B, O>) -> O {
+    match value {
+        DecodeDifferent::Decoded(value) => value,
+        DecodeDifferent::Encode(_) => unreachable!("metadata is decoded before use; qed"),
+    }
+}
+
+/// Every type name used by storage, calls, events and constants in the runtime metadata, with
+/// the name of the module using it.
+fn metadata_types() -> Result<BTreeSet<(String, String)>, String> {
+    // decode the metadata so its fields are owned rather than native encoders
+    let metadata = RuntimeMetadataPrefixed::decode(&mut &Runtime::metadata().encode()[..])
+        .map_err(|e| format!("Invalid runtime metadata: {}", e))?;
+    let modules = match metadata.1 {
+        RuntimeMetadata::V12(metadata) => decoded(metadata.modules),
+        _ => return Err("Unsupported runtime metadata version".into()),
+    };
+
+    let mut used = BTreeSet::new();
+    for module in modules {
+        let mut types = Vec::new();
+        if let Some(storage) = module.storage {
+            for entry in decoded(decoded(storage).entries) {
+                match entry.ty {
+                    StorageEntryType::Plain(value) => types.push(decoded(value)),
+                    StorageEntryType::Map { key, value, .. } => {
+                        types.push(decoded(key));
+                        types.push(decoded(value));
+                    }
+                    StorageEntryType::DoubleMap {
+                        key1, key2, value, ..
+                    } => {
+                        types.push(decoded(key1));
+                        types.push(decoded(key2));
+                        types.push(decoded(value));
+                    }
+                }
+            }
+        }
+
+        for call in module.calls.map(decoded).unwrap_or_default() {
+            types.extend(decoded(call.arguments).into_iter().map(|arg| decoded(arg.ty)));
+        }
+        for event in module.event.map(decoded).unwrap_or_default() {
+            types.extend(decoded(event.arguments));
+        }
+        for constant in decoded(module.constants) {
+            types.push(decoded(constant.ty));
+        }
+
+        let name = decoded(module.name);
+        used.extend(
+            types
+                .iter()
+                .flat_map(|ty| type_names(ty))
+                .map(|ty| (name.clone(), ty)),
+        );
+    }
+
+    Ok(used)
+}
+
+/// Add the type names used by a custom type definition to `types`.
+fn definition_types(definition: &Value, types: &mut BTreeSet<String>) {
+    match definition {
+        Value::String(ty) => types.extend(type_names(ty)),
+        Value::Object(fields) => match fields.get("_enum") {
+            Some(Value::Object(variants)) => variants
+                .values()
+                .for_each(|variant| definition_types(variant, types)),
+            // an enum without data, listing variant names only
+            Some(_) => {}
+            None => fields
+                .values()
+                .for_each(|field| definition_types(field, types)),
+        },
+        _ => {}
+    }
+}
+
+/// The type names in a Rust type as written in metadata, read the way polkadot.js reads them:
+/// `<T as Trait>::Name` and `T::Name` are just `Name`.
+fn type_names(ty: &str) -> Vec<String> {
+    let mut ty = ty.replace("<T::Lookup as StaticLookup>::Source", "LookupSource");
+    while let Some(start) = ty.find(" as ") {
+        let open = ty[..start].rfind('<').unwrap_or(0);
+        let close = ty[start..]
+            .find(">::")
+            .map_or(ty.len(), |end| start + end + ">::".len());
+        ty.replace_range(open..close, "");
+    }
+
+    ty.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':'))
+        .filter_map(|path| path.rsplit("::").next())
+        // skip array lengths and the `T` and `I` parameters of pallets
+        .filter(|name| {
+            !name.is_empty()
+                && !name.starts_with(|c: char| c.is_ascii_digit())
+                && *name != "T"
+                && *name != "I"
+        })
+        .map(String::from)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn type_names_should_work() {
+        assert_eq!(type_names("Vec<T::AccountId>"), vec!["Vec", "AccountId"]);
+        assert_eq!(type_names("Box<<T as Trait>::Call>"), vec!["Box", "Call"]);
+        assert_eq!(type_names("<T::Lookup as StaticLookup>::Source"), vec!["LookupSource"]);
+        assert_eq!(
+            type_names("(ClassIdOf<T>, TokenIdOf<T>)"),
+            vec!["ClassIdOf", "TokenIdOf"]
+        );
+        assert_eq!(type_names("Option<[u8; 20]>"), vec!["Option", "u8"]);
+        assert_eq!(type_names("orml_nft::CID"), vec!["CID"]);
+    }
+
+    #[test]
+    fn runtime_types_are_defined() {
+        assert_eq!(missing_types(&type_definitions()), Ok(vec![]));
+    }
+
+    #[test]
+    fn module_types_are_only_built_in_for_their_modules() {
+        let used = |module: &str, ty: &str| -> BTreeSet<(String, String)> {
+            std::iter::once((module.to_string(), ty.to_string())).collect()
+        };
+        let defined = Map::new();
+
+        assert!(undefined(used("Balances", "Releases"), &defined).is_empty());
+        assert!(undefined(used("Indices", "AccountIndex"), &defined).is_empty());
+        assert_eq!(undefined(used("OrmlNFT", "Releases"), &defined), vec!["Releases"]);
+        assert_eq!(undefined(used("", "Releases"), &defined), vec!["Releases"]);
+    }
+}
@@ -7,13 +7,19 @@
 
 use std::sync::Arc;
 
-use loot_node_runtime::{opaque::Block, AccountId, Balance, Index};
+use futures::channel::mpsc::Sender;
+use loot_node_runtime::{opaque::Block, AccountId, Balance, ClassId, Hash, Index, TokenId};
+use sc_client_api::{Backend, BlockchainEvents, StorageProvider};
+use sc_consensus_manual_seal::EngineCommand;
+use sc_rpc::SubscriptionTaskExecutor;
 pub use sc_rpc_api::DenyUnsafe;
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 use sp_transaction_pool::TransactionPool;
 
+pub mod market_events;
+
 /// Full client dependencies.
 pub struct FullDeps<C, P> {
     /// The client instance to use.
@@ -22,20 +28,32 @@ pub struct FullDeps<C, P> {
     pub pool: Arc<P>,
     /// Whether to deny unsafe calls
     pub deny_unsafe: DenyUnsafe,
+    /// Executor for pubsub subscriptions.
+    pub subscription_executor: SubscriptionTaskExecutor,
+    /// Where to send the commands of the manual seal RPC, if the node seals blocks itself.
+    pub command_sink: Option<Sender<EngineCommand<Hash>>>,
 }
 
 /// Instantiate all full RPC extensions.
-pub fn create_full<C, P>(deps: FullDeps<C, P>) -> jsonrpc_core::IoHandler<sc_rpc::Metadata>
+pub fn create_full<C, P, B>(deps: FullDeps<C, P>) -> jsonrpc_core::IoHandler<sc_rpc::Metadata>
 where
+    B: Backend<Block> + Send + Sync + 'static,
     C: ProvideRuntimeApi<Block>,
+    C: BlockchainEvents<Block> + StorageProvider<Block, B>,
     C: HeaderBackend<Block> + HeaderMetadata<Block, Error = BlockChainError> + 'static,
     C: Send + Sync + 'static,
     C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
     C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
+    C::Api: royalty_nft_rpc::RoyaltyRuntimeApi<Block, AccountId, ClassId, TokenId, Balance>,
+    C::Api: pallet_pause_rpc::PauseRuntimeApi<Block>,
     C::Api: BlockBuilder<Block>,
     P: TransactionPool + 'static,
 {
     use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
+    use market_events::{MarketEvents, MarketEventsApi};
+    use pallet_pause_rpc::{Pause, PauseApi};
+    use sc_consensus_manual_seal::rpc::{ManualSeal, ManualSealApi};
+    use royalty_nft_rpc::{RoyaltyNft, RoyaltyNftApi};
     use substrate_frame_rpc_system::{FullSystem, SystemApi};
 
     let mut io = jsonrpc_core::IoHandler::default();
@@ -43,6 +61,8 @@ where
         client,
         pool,
         deny_unsafe,
+        subscription_executor,
+        command_sink,
     } = deps;
 
     io.extend_with(SystemApi::to_delegate(FullSystem::new(
@@ -55,6 +75,20 @@ where
         client.clone(),
     )));
 
+    io.extend_with(RoyaltyNftApi::to_delegate(RoyaltyNft::new(client.clone())));
+
+    io.extend_with(PauseApi::to_delegate(Pause::new(client.clone())));
+
+    io.extend_with(MarketEventsApi::to_delegate(MarketEvents::<_, B>::new(
+        client.clone(),
+        subscription_executor,
+    )));
+
+    if let Some(command_sink) = command_sink {
+        // `engine_createBlock` and `engine_finalizeBlock`
+        io.extend_with(ManualSealApi::to_delegate(ManualSeal::new(command_sink)));
+    }
+
     // Extend this RPC with a custom API by using the following syntax.
     // `YourRpcStruct` should have a reference to a client, which is needed
     // to call into the runtime.
@@ -0,0 +1,563 @@
+//! `loot_subscribeMarketEvents`: a pubsub RPC that streams decoded `royalty_nft` events.
+//!
+//! Events are read from `frame_system::Events` of every new best block announced on the
+//! client's import notification stream, decoded with the native runtime types and pushed to
+//! subscribers whose filter matches them. Blocks that are later retracted are not un-sent, so
+//! indexers that need finality should still confirm against finalized blocks.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::Decode;
+use frame_system::EventRecord;
+use futures::{StreamExt, TryStreamExt};
+use jsonrpc_core::{
+    futures::{Future, Sink},
+    Result as RpcResult,
+};
+use jsonrpc_derive::rpc;
+use jsonrpc_pubsub::{manager::SubscriptionManager, typed::Subscriber, SubscriptionId};
+use log::warn;
+use loot_node_runtime::{
+    opaque::Block, royalty_nft::RawEvent, AccountId, Balance, ClassId, Event, Hash, TokenId,
+};
+use sc_client_api::{Backend, BlockchainEvents, StorageProvider};
+use sc_rpc::SubscriptionTaskExecutor;
+use serde::{Deserialize, Serialize};
+use sp_core::{storage::StorageKey, twox_128};
+use sp_runtime::generic::BlockId;
+
+/// A marketplace event, decoded from a `royalty_nft` runtime event.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase", tag = "type")]
+pub enum MarketEventKind {
+    /// A new class was created.
+    ClassCreated {
+        /// Owner of the class.
+        owner: AccountId,
+        /// The new class.
+        class_id: ClassId,
+        /// Price of minting a token in the class.
+        mint_price: Balance,
+        /// Royalty percentage paid to the owner on sales.
+        royalty: u32,
+        /// Whether tokens of the class can never change owner.
+        soulbound: bool,
+    },
+    /// The minting info of a class changed.
+    ClassInfoUpdated {
+        /// The class.
+        class_id: ClassId,
+        /// Whether tokens can be minted.
+        can_mint: bool,
+        /// Price of minting a token in the class.
+        mint_price: Balance,
+        /// Royalty percentage paid to the owner on sales.
+        royalty: u32,
+    },
+    /// A token was minted.
+    TokenMinted {
+        /// Account the token was minted to.
+        owner: AccountId,
+        /// Class of the token.
+        class_id: ClassId,
+        /// The new token.
+        token_id: TokenId,
+        /// Price paid to the class owner.
+        mint_price: Balance,
+    },
+    /// A token changed hands through `nft_transfer`.
+    TokenTransferred {
+        /// Previous owner.
+        from: AccountId,
+        /// New owner.
+        to: AccountId,
+        /// Class of the token.
+        class_id: ClassId,
+        /// The token.
+        token_id: TokenId,
+    },
+    /// A royalty was paid to a class owner.
+    RoyaltySent {
+        /// Account that paid the royalty.
+        payer: AccountId,
+        /// Class owner receiving the royalty.
+        recipient: AccountId,
+        /// Class the royalty was paid for.
+        class_id: ClassId,
+        /// Royalty amount.
+        royalty: Balance,
+    },
+    /// The platform fee of a sale or buyout was paid.
+    PlatformFeePaid {
+        /// Account that paid the fee.
+        payer: AccountId,
+        /// Class of the token sold.
+        class_id: ClassId,
+        /// Fee amount.
+        fee: Balance,
+    },
+    /// A token was listed for sale.
+    TokenSaleCreated {
+        /// Owner of the token.
+        seller: AccountId,
+        /// Class of the token.
+        class_id: ClassId,
+        /// The token.
+        token_id: TokenId,
+        /// Asking price.
+        price: Balance,
+    },
+    /// A listing was withdrawn.
+    TokenSaleDeleted {
+        /// Owner of the token.
+        seller: AccountId,
+        /// Class of the token.
+        class_id: ClassId,
+        /// The token.
+        token_id: TokenId,
+    },
+    /// A listed token was bought.
+    TokenSaleCompleted {
+        /// Previous owner.
+        seller: AccountId,
+        /// The new owner.
+        buyer: AccountId,
+        /// Class of the token.
+        class_id: ClassId,
+        /// The token.
+        token_id: TokenId,
+        /// Price paid by the buyer.
+        price: Balance,
+        /// Part of the price paid to the class owner.
+        royalty: Balance,
+    },
+    /// A token was burned.
+    TokenBurned {
+        /// Owner of the token.
+        owner: AccountId,
+        /// Class of the token.
+        class_id: ClassId,
+        /// The token.
+        token_id: TokenId,
+    },
+    /// A token was nested in another token, and now moves with it.
+    TokenNested {
+        /// Root owner of the token before it was nested.
+        owner: AccountId,
+        /// Class of the token.
+        class_id: ClassId,
+        /// The token.
+        token_id: TokenId,
+        /// Class of the token it was nested in.
+        parent_class_id: ClassId,
+        /// The token it was nested in.
+        parent_token_id: TokenId,
+    },
+    /// A token was locked in the vault and split into shares.
+    TokenFractionalized {
+        /// Owner of the token, who received all the shares.
+        owner: AccountId,
+        /// Class of the token.
+        class_id: ClassId,
+        /// The token.
+        token_id: TokenId,
+        /// Number of shares issued.
+        shares: u64,
+        /// Price at which anyone can buy the token out of the vault.
+        buyout_price: Balance,
+    },
+    /// Shares of a fractionalized token were transferred.
+    SharesTransferred {
+        /// Sender of the shares.
+        from: AccountId,
+        /// Recipient of the shares.
+        to: AccountId,
+        /// Class of the token.
+        class_id: ClassId,
+        /// The token.
+        token_id: TokenId,
+        /// Number of shares transferred.
+        shares: u64,
+    },
+    /// All shares of a token were redeemed for the token.
+    TokenRedeemed {
+        /// New owner of the token.
+        owner: AccountId,
+        /// Class of the token.
+        class_id: ClassId,
+        /// The token.
+        token_id: TokenId,
+    },
+    /// A fractionalized token was bought out of the vault.
+    TokenBoughtOut {
+        /// New owner of the token.
+        buyer: AccountId,
+        /// Class of the token.
+        class_id: ClassId,
+        /// The token.
+        token_id: TokenId,
+        /// Price paid by the buyer.
+        price: Balance,
+        /// Part of the price paid to the class owner.
+        royalty: Balance,
+    },
+    /// A class was frozen; none of its tokens can change owner until it is thawed.
+    ClassFrozen {
+        /// The class.
+        class_id: ClassId,
+    },
+    /// A class was thawed.
+    ClassThawed {
+        /// The class.
+        class_id: ClassId,
+    },
+    /// A token was frozen; it can't change owner until it is thawed.
+    TokenFrozen {
+        /// Class of the token.
+        class_id: ClassId,
+        /// The token.
+        token_id: TokenId,
+    },
+    /// A token was thawed.
+    TokenThawed {
+        /// Class of the token.
+        class_id: ClassId,
+        /// The token.
+        token_id: TokenId,
+    },
+}
+
+impl MarketEventKind {
+    fn from_event(event: Event) -> Option<Self> {
+        let event = match event {
+            Event::royalty_nft(event) => event,
+            _ => return None,
+        };
+
+        Some(match event {
+            RawEvent::ClassCreated(owner, class_id, mint_price, royalty, soulbound) => {
+                Self::ClassCreated {
+                    owner,
+                    class_id,
+                    mint_price,
+                    royalty,
+                    soulbound,
+                }
+            }
+            RawEvent::ClassInfoUpdated(class_id, can_mint, mint_price, royalty) => {
+                Self::ClassInfoUpdated {
+                    class_id,
+                    can_mint,
+                    mint_price,
+                    royalty,
+                }
+            }
+            RawEvent::TokenMinted(owner, class_id, token_id, mint_price) => Self::TokenMinted {
+                owner,
+                class_id,
+                token_id,
+                mint_price,
+            },
+            RawEvent::TokenTransferred(from, to, class_id, token_id) => Self::TokenTransferred {
+                from,
+                to,
+                class_id,
+                token_id,
+            },
+            RawEvent::RoyaltySent(payer, recipient, class_id, royalty) => Self::RoyaltySent {
+                payer,
+                recipient,
+                class_id,
+                royalty,
+            },
+            RawEvent::PlatformFeePaid(payer, class_id, fee) => Self::PlatformFeePaid {
+                payer,
+                class_id,
+                fee,
+            },
+            RawEvent::TokenSaleCreated(seller, class_id, token_id, price) => {
+                Self::TokenSaleCreated {
+                    seller,
+                    class_id,
+                    token_id,
+                    price,
+                }
+            }
+            RawEvent::TokenSaleDeleted(seller, class_id, token_id) => Self::TokenSaleDeleted {
+                seller,
+                class_id,
+                token_id,
+            },
+            RawEvent::TokenSaleCompleted(seller, buyer, class_id, token_id, price, royalty) => {
+                Self::TokenSaleCompleted {
+                    seller,
+                    buyer,
+                    class_id,
+                    token_id,
+                    price,
+                    royalty,
+                }
+            }
+            RawEvent::TokenBurned(owner, class_id, token_id) => Self::TokenBurned {
+                owner,
+                class_id,
+                token_id,
+            },
+            RawEvent::TokenNested(owner, class_id, token_id, parent_class_id, parent_token_id) => {
+                Self::TokenNested {
+                    owner,
+                    class_id,
+                    token_id,
+                    parent_class_id,
+                    parent_token_id,
+                }
+            }
+            RawEvent::TokenFractionalized(owner, class_id, token_id, shares, buyout_price) => {
+                Self::TokenFractionalized {
+                    owner,
+                    class_id,
+                    token_id,
+                    shares,
+                    buyout_price,
+                }
+            }
+            RawEvent::SharesTransferred(from, to, class_id, token_id, shares) => {
+                Self::SharesTransferred {
+                    from,
+                    to,
+                    class_id,
+                    token_id,
+                    shares,
+                }
+            }
+            RawEvent::TokenRedeemed(owner, class_id, token_id) => Self::TokenRedeemed {
+                owner,
+                class_id,
+                token_id,
+            },
+            RawEvent::TokenBoughtOut(buyer, class_id, token_id, price, royalty) => {
+                Self::TokenBoughtOut {
+                    buyer,
+                    class_id,
+                    token_id,
+                    price,
+                    royalty,
+                }
+            }
+            RawEvent::ClassFrozen(class_id) => Self::ClassFrozen { class_id },
+            RawEvent::ClassThawed(class_id) => Self::ClassThawed { class_id },
+            RawEvent::TokenFrozen(class_id, token_id) => Self::TokenFrozen { class_id, token_id },
+            RawEvent::TokenThawed(class_id, token_id) => Self::TokenThawed { class_id, token_id },
+        })
+    }
+
+    fn class_id(&self) -> ClassId {
+        match self {
+            Self::ClassCreated { class_id, .. }
+            | Self::ClassInfoUpdated { class_id, .. }
+            | Self::TokenMinted { class_id, .. }
+            | Self::TokenTransferred { class_id, .. }
+            | Self::RoyaltySent { class_id, .. }
+            | Self::PlatformFeePaid { class_id, .. }
+            | Self::TokenSaleCreated { class_id, .. }
+            | Self::TokenSaleDeleted { class_id, .. }
+            | Self::TokenSaleCompleted { class_id, .. }
+            | Self::TokenBurned { class_id, .. }
+            | Self::TokenNested { class_id, .. }
+            | Self::TokenFractionalized { class_id, .. }
+            | Self::SharesTransferred { class_id, .. }
+            | Self::TokenRedeemed { class_id, .. }
+            | Self::TokenBoughtOut { class_id, .. }
+            | Self::ClassFrozen { class_id }
+            | Self::ClassThawed { class_id }
+            | Self::TokenFrozen { class_id, .. }
+            | Self::TokenThawed { class_id, .. } => *class_id,
+        }
+    }
+
+    fn involves(&self, account: &AccountId) -> bool {
+        match self {
+            Self::ClassCreated { owner, .. }
+            | Self::TokenMinted { owner, .. }
+            | Self::TokenBurned { owner, .. }
+            | Self::TokenNested { owner, .. }
+            | Self::TokenFractionalized { owner, .. }
+            | Self::TokenRedeemed { owner, .. } => owner == account,
+            Self::TokenBoughtOut { buyer, .. } => buyer == account,
+            Self::PlatformFeePaid { payer, .. } => payer == account,
+            Self::ClassInfoUpdated { .. }
+            | Self::ClassFrozen { .. }
+            | Self::ClassThawed { .. }
+            | Self::TokenFrozen { .. }
+            | Self::TokenThawed { .. } => false,
+            Self::TokenTransferred { from, to, .. } | Self::SharesTransferred { from, to, .. } => {
+                from == account || to == account
+            }
+            Self::RoyaltySent {
+                payer, recipient, ..
+            } => payer == account || recipient == account,
+            Self::TokenSaleCreated { seller, .. } | Self::TokenSaleDeleted { seller, .. } => {
+                seller == account
+            }
+            Self::TokenSaleCompleted { seller, buyer, .. } => seller == account || buyer == account,
+        }
+    }
+}
+
+/// A marketplace event together with the block it was emitted in.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketEvent {
+    /// Hash of the block that emitted the event.
+    pub block_hash: Hash,
+    /// The decoded event.
+    pub event: MarketEventKind,
+}
+
+/// Restricts a subscription to the events of one class and/or one account. Unset fields match
+/// everything.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MarketEventFilter {
+    /// Only send events of this class.
+    pub class_id: Option<ClassId>,
+    /// Only send events this account takes part in.
+    pub account: Option<AccountId>,
+}
+
+impl MarketEventFilter {
+    fn matches(&self, event: &MarketEventKind) -> bool {
+        self.class_id.map_or(true, |class_id| event.class_id() == class_id)
+            && self.account.as_ref().map_or(true, |account| event.involves(account))
+    }
+}
+
+/// Marketplace event subscriptions.
+#[rpc]
+pub trait MarketEventsApi {
+    /// RPC metadata
+    type Metadata;
+
+    /// Subscribe to marketplace events of new best blocks.
+    #[pubsub(
+        subscription = "loot_marketEvents",
+        subscribe,
+        name = "loot_subscribeMarketEvents"
+    )]
+    fn subscribe_market_events(
+        &self,
+        metadata: Self::Metadata,
+        subscriber: Subscriber<MarketEvent>,
+        filter: Option<MarketEventFilter>,
+    );
+
+    /// Unsubscribe from marketplace events.
+    #[pubsub(
+        subscription = "loot_marketEvents",
+        unsubscribe,
+        name = "loot_unsubscribeMarketEvents"
+    )]
+    fn unsubscribe_market_events(
+        &self,
+        metadata: Option<Self::Metadata>,
+        id: SubscriptionId,
+    ) -> RpcResult<bool>;
+}
+
+/// Implements [`MarketEventsApi`] on top of the client's import notifications.
+pub struct MarketEvents<C, B> {
+    client: Arc<C>,
+    manager: SubscriptionManager,
+    _marker: PhantomData<B>,
+}
+
+impl<C, B> MarketEvents<C, B> {
+    /// Create new `MarketEvents` with the given reference to the client.
+    pub fn new(client: Arc<C>, executor: SubscriptionTaskExecutor) -> Self {
+        MarketEvents {
+            client,
+            manager: SubscriptionManager::new(Arc::new(executor)),
+            _marker: Default::default(),
+        }
+    }
+}
+
+/// Storage key of `frame_system::Events`.
+fn events_key() -> StorageKey {
+    StorageKey([twox_128(b"System"), twox_128(b"Events")].concat())
+}
+
+/// Read and decode the marketplace events emitted in block `hash`.
+fn market_events<C, B>(client: &C, hash: Hash) -> Result<Vec<MarketEvent>, String>
+where
+    B: Backend<Block>,
+    C: StorageProvider<Block, B>,
+{
+    let raw = client
+        .storage(&BlockId::Hash(hash), &events_key())
+        .map_err(|e| format!("{:?}", e))?;
+    let records: Vec<EventRecord<Event, Hash>> = match raw {
+        Some(data) => Decode::decode(&mut &data.0[..]).map_err(|e| format!("{:?}", e))?,
+        None => Vec::new(),
+    };
+
+    Ok(records
+        .into_iter()
+        .filter_map(|record| MarketEventKind::from_event(record.event))
+        .map(|event| MarketEvent {
+            block_hash: hash,
+            event,
+        })
+        .collect())
+}
+
+impl<C, B> MarketEventsApi for MarketEvents<C, B>
+where
+    B: Backend<Block> + Send + Sync + 'static,
+    C: BlockchainEvents<Block> + StorageProvider<Block, B> + Send + Sync + 'static,
+{
+    type Metadata = sc_rpc::Metadata;
+
+    fn subscribe_market_events(
+        &self,
+        _metadata: Self::Metadata,
+        subscriber: Subscriber<MarketEvent>,
+        filter: Option<MarketEventFilter>,
+    ) {
+        let client = self.client.clone();
+        let filter = filter.unwrap_or_default();
+
+        let stream = self
+            .client
+            .import_notification_stream()
+            .filter(|notification| futures::future::ready(notification.is_new_best))
+            .map(move |notification| {
+                let events = market_events::<_, B>(&*client, notification.hash).unwrap_or_else(|e| {
+                    warn!("Unable to read market events of {}: {}", notification.hash, e);
+                    Vec::new()
+                });
+                let filter = filter.clone();
+
+                futures::stream::iter(events)
+                    .filter(move |event| futures::future::ready(filter.matches(&event.event)))
+            })
+            .flatten()
+            .map(|event| Ok::<_, ()>(Ok(event)))
+            .compat();
+
+        self.manager.add(subscriber, |sink| {
+            sink.sink_map_err(|e| warn!("Error sending market events: {:?}", e))
+                .send_all(stream)
+                // we ignore the resulting Stream (if the first stream is over we are unsubscribed)
+                .map(|_| ())
+        });
+    }
+
+    fn unsubscribe_market_events(
+        &self,
+        _metadata: Option<Self::Metadata>,
+        id: SubscriptionId,
+    ) -> RpcResult<bool> {
+        Ok(self.manager.cancel(id))
+    }
+}
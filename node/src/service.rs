@@ -1,17 +1,28 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
-use loot_node_runtime::{self, opaque::Block, RuntimeApi};
-use sc_client_api::{ExecutorProvider, RemoteBackend};
+use crate::chain_spec::Extensions;
+use crate::cli::Sealing;
+use codec::Decode;
+use futures::StreamExt;
+use loot_node_runtime::{self, opaque::Block, Hash, RuntimeApi, SLOT_DURATION};
+use sc_client_api::{ExecutorProvider, RemoteBackend, StorageProvider};
+use sc_consensus_manual_seal::{EngineCommand, ManualSealParams};
 use sc_executor::native_executor_instance;
 pub use sc_executor::NativeExecutor;
 use sc_finality_grandpa::{
     FinalityProofProvider as GrandpaFinalityProofProvider, SharedVoterState,
 };
 use sc_service::{error::Error as ServiceError, Configuration, TaskManager};
+use sp_blockchain::HeaderBackend;
 use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
-use sp_inherents::InherentDataProviders;
-use std::sync::Arc;
-use std::time::Duration;
+use sp_core::{storage::StorageKey, twox_128};
+use sp_inherents::{InherentData, InherentDataProviders, InherentIdentifier, ProvideInherentData};
+use sp_runtime::generic::BlockId;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 // Our native executor instance.
 native_executor_instance!(
@@ -102,8 +113,17 @@ pub fn new_partial(
     })
 }
 
-/// Builds a new service for a full client.
-pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
+/// Builds a new service for a full client, with the consensus settings of `extensions`. With
+/// `sealing` set, blocks are sealed on demand instead, see `new_manual_seal`.
+pub fn new_full(
+    config: Configuration,
+    extensions: Extensions,
+    sealing: Option<Sealing>,
+) -> Result<TaskManager, ServiceError> {
+    if let Some(sealing) = sealing {
+        return new_manual_seal(config, sealing);
+    }
+
     let sc_service::PartialComponents {
         client,
         backend,
@@ -143,7 +163,7 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
     }
 
     let role = config.role.clone();
-    let force_authoring = config.force_authoring;
+    let force_authoring = config.force_authoring || extensions.aura.force_authoring;
     let name = config.network.node_name.clone();
     let enable_grandpa = !config.disable_grandpa;
     let prometheus_registry = config.prometheus_registry().cloned();
@@ -153,14 +173,16 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
         let client = client.clone();
         let pool = transaction_pool.clone();
 
-        Box::new(move |deny_unsafe, _| {
+        Box::new(move |deny_unsafe, subscription_executor| {
             let deps = crate::rpc::FullDeps {
                 client: client.clone(),
                 pool: pool.clone(),
                 deny_unsafe,
+                subscription_executor,
+                command_sink: None,
             };
 
-            crate::rpc::create_full(deps)
+            crate::rpc::create_full::<_, _, FullBackend>(deps)
         })
     };
 
@@ -219,9 +241,8 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
     };
 
     let grandpa_config = sc_finality_grandpa::Config {
-        // FIXME #1578 make this available through chainspec
-        gossip_duration: Duration::from_millis(333),
-        justification_period: 512,
+        gossip_duration: Duration::from_millis(extensions.grandpa.gossip_duration),
+        justification_period: extensions.grandpa.justification_period,
         name: Some(name),
         observer_enabled: false,
         keystore,
@@ -260,6 +281,193 @@ pub fn new_full(config: Configuration) -> Result<TaskManager, ServiceError> {
     Ok(task_manager)
 }
 
+/// Builds a new service for a full client that seals blocks when `sealing` says so rather than
+/// authoring them with Aura, so tests don't wait for slots. Blocks sealed by `instant` and
+/// `interval` are finalized right away; there is no GRANDPA voter, so other blocks are finalized
+/// with the `engine_finalizeBlock` RPC.
+fn new_manual_seal(config: Configuration, sealing: Sealing) -> Result<TaskManager, ServiceError> {
+    let (client, backend, keystore, mut task_manager) =
+        sc_service::new_full_parts::<Block, RuntimeApi, Executor>(&config)?;
+    let client = Arc::new(client);
+
+    let select_chain = sc_consensus::LongestChain::new(backend.clone());
+
+    let transaction_pool = sc_transaction_pool::BasicPool::new_full(
+        config.transaction_pool.clone(),
+        config.prometheus_registry(),
+        task_manager.spawn_handle(),
+        client.clone(),
+    );
+
+    let import_queue = sc_consensus_manual_seal::import_queue(
+        Box::new(client.clone()),
+        &task_manager.spawn_handle(),
+        config.prometheus_registry(),
+    );
+
+    let (network, network_status_sinks, system_rpc_tx, network_starter) =
+        sc_service::build_network(sc_service::BuildNetworkParams {
+            config: &config,
+            client: client.clone(),
+            transaction_pool: transaction_pool.clone(),
+            spawn_handle: task_manager.spawn_handle(),
+            import_queue,
+            on_demand: None,
+            block_announce_validator_builder: None,
+            finality_proof_request_builder: None,
+            finality_proof_provider: None,
+        })?;
+
+    if config.offchain_worker.enabled {
+        sc_service::build_offchain_workers(
+            &config,
+            backend.clone(),
+            task_manager.spawn_handle(),
+            client.clone(),
+            network.clone(),
+        );
+    }
+
+    let inherent_data_providers = InherentDataProviders::new();
+    inherent_data_providers
+        .register_provider(SlotTimestampProvider::new(&*client)?)
+        .map_err(sp_consensus::Error::InherentData)?;
+
+    let prometheus_registry = config.prometheus_registry().cloned();
+    let (command_sink, rpc_commands) = futures::channel::mpsc::channel(1024);
+
+    let rpc_extensions_builder = {
+        let client = client.clone();
+        let pool = transaction_pool.clone();
+
+        Box::new(move |deny_unsafe, subscription_executor| {
+            let deps = crate::rpc::FullDeps {
+                client: client.clone(),
+                pool: pool.clone(),
+                deny_unsafe,
+                subscription_executor,
+                command_sink: Some(command_sink.clone()),
+            };
+
+            crate::rpc::create_full::<_, _, FullBackend>(deps)
+        })
+    };
+
+    sc_service::spawn_tasks(sc_service::SpawnTasksParams {
+        network: network.clone(),
+        client: client.clone(),
+        keystore,
+        task_manager: &mut task_manager,
+        transaction_pool: transaction_pool.clone(),
+        telemetry_connection_sinks: sc_service::TelemetryConnectionSinks::default(),
+        rpc_extensions_builder,
+        on_demand: None,
+        remote_blockchain: None,
+        backend,
+        network_status_sinks,
+        system_rpc_tx,
+        config,
+    })?;
+
+    let seal_commands = match sealing {
+        Sealing::Manual => futures::stream::empty().boxed(),
+        Sealing::Instant => transaction_pool
+            .pool()
+            .validated_pool()
+            .import_notification_stream()
+            .map(|_| seal_and_finalize(false))
+            .boxed(),
+        Sealing::Interval(millis) => futures::stream::unfold((), move |()| async move {
+            futures_timer::Delay::new(Duration::from_millis(millis)).await;
+            Some((seal_and_finalize(true), ()))
+        })
+        .boxed(),
+    };
+
+    let proposer = sc_basic_authorship::ProposerFactory::new(
+        client.clone(),
+        transaction_pool.clone(),
+        prometheus_registry.as_ref(),
+    );
+
+    let authorship = sc_consensus_manual_seal::run_manual_seal(ManualSealParams {
+        block_import: client.clone(),
+        env: proposer,
+        client,
+        pool: transaction_pool.pool().clone(),
+        commands_stream: futures::stream::select(rpc_commands, seal_commands),
+        select_chain,
+        consensus_data_provider: None,
+        inherent_data_providers,
+    });
+
+    // as with Aura, the node can't do without its block authoring task
+    task_manager
+        .spawn_essential_handle()
+        .spawn_blocking("manual-seal", authorship);
+
+    network_starter.start_network();
+    Ok(task_manager)
+}
+
+/// Seal a block on top of the best block and finalize it, unless it would be empty and
+/// `create_empty` is not set.
+fn seal_and_finalize(create_empty: bool) -> EngineCommand<Hash> {
+    EngineCommand::SealNewBlock {
+        create_empty,
+        finalize: true,
+        parent_hash: None,
+        sender: None,
+    }
+}
+
+/// Timestamps a slot apart for the blocks sealed by `new_manual_seal`. The runtime allows only
+/// one block per Aura slot, so wall clock timestamps would fail blocks sealed in quick succession.
+struct SlotTimestampProvider {
+    next: AtomicU64,
+}
+
+impl SlotTimestampProvider {
+    /// Start from the current time, or a slot after the best block if that is later, as it is
+    /// once blocks have been sealed faster than the slot duration.
+    fn new(client: &FullClient) -> Result<Self, ServiceError> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("the current time is after the unix epoch; qed")
+            .as_millis() as u64;
+
+        let key = StorageKey([twox_128(b"Timestamp"), twox_128(b"Now")].concat());
+        let best = BlockId::Hash(client.info().best_hash);
+        let last = client
+            .storage(&best, &key)?
+            .and_then(|data| u64::decode(&mut &data.0[..]).ok())
+            .unwrap_or_default();
+
+        Ok(SlotTimestampProvider {
+            next: AtomicU64::new(now.max(last + SLOT_DURATION)),
+        })
+    }
+}
+
+impl ProvideInherentData for SlotTimestampProvider {
+    fn inherent_identifier(&self) -> &'static InherentIdentifier {
+        &sp_timestamp::INHERENT_IDENTIFIER
+    }
+
+    fn provide_inherent_data(
+        &self,
+        inherent_data: &mut InherentData,
+    ) -> Result<(), sp_inherents::Error> {
+        let timestamp = self.next.fetch_add(SLOT_DURATION, Ordering::SeqCst);
+        inherent_data.put_data(sp_timestamp::INHERENT_IDENTIFIER, &timestamp)
+    }
+
+    fn error_to_string(&self, error: &[u8]) -> Option<String> {
+        sp_timestamp::InherentError::try_from(&sp_timestamp::INHERENT_IDENTIFIER, error)
+            .map(|e| format!("{:?}", e))
+    }
+}
+
 /// Builds a new service for a light client.
 pub fn new_light(config: Configuration) -> Result<TaskManager, ServiceError> {
     let (client, backend, keystore, mut task_manager, on_demand) =
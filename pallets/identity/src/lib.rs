@@ -84,12 +84,14 @@ use frame_support::{
     dispatch::DispatchResultWithPostInfo,
     ensure,
     traits::{BalanceStatus, Currency, EnsureOrigin, Get, OnUnbalanced, ReservableCurrency},
+    weights::Weight,
+    IterableStorageMap,
 };
-use frame_system::ensure_signed;
+use frame_system::{ensure_signed, RawOrigin};
 use sp_runtime::traits::{AppendZerosInput, Saturating, StaticLookup, Zero};
 use sp_runtime::{DispatchError, DispatchResult, RuntimeDebug};
 use sp_std::prelude::*;
-use sp_std::{fmt::Debug, iter::once, ops::Add};
+use sp_std::{fmt::Debug, iter::once, marker::PhantomData, ops::Add};
 pub use weights::WeightInfo;
 
 type BalanceOf<T> =
@@ -410,6 +412,22 @@ pub struct RegistrarInfo<
     pub fields: IdentityFields,
 }
 
+/// Storage layouts of this pallet, for migrations. Named apart from the `Releases` of other
+/// pallets, as polkadot.js knows types by name only.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum IdentityReleases {
+    /// Identities only.
+    V1,
+    /// Identities indexed by username.
+    V2,
+}
+
+impl Default for IdentityReleases {
+    fn default() -> Self {
+        IdentityReleases::V1
+    }
+}
+
 decl_storage! {
     trait Store for Module<T: Trait> as Identity {
         /// Information that is pertinent to identify the entity behind an account.
@@ -436,6 +454,14 @@ decl_storage! {
         ///
         /// The index into this can be cast to `RegistrarIndex` to get a valid value.
         pub Registrars get(fn registrars): Vec<Option<RegistrarInfo<BalanceOf<T>, T::AccountId>>>;
+
+        /// The account whose identity has a display name of `Data::Raw`, by that name in lower
+        /// case. Such display names are unique, so they double as usernames.
+        pub Usernames get(fn username_owner):
+            map hasher(blake2_128_concat) Vec<u8> => Option<T::AccountId>;
+
+        /// Storage layout, for migrations.
+        StorageVersion: IdentityReleases;
     }
 }
 
@@ -504,7 +530,9 @@ decl_error! {
         /// Sender is not a sub-account.
         NotSub,
         /// Sub-account isn't owned by sender.
-        NotOwned
+        NotOwned,
+        /// Display name is already the username of another account.
+        UsernameTaken,
     }
 }
 
@@ -537,6 +565,16 @@ decl_module! {
 
         fn deposit_event() = default;
 
+        fn on_runtime_upgrade() -> Weight {
+            if StorageVersion::get() == IdentityReleases::V1 {
+                let weight = Self::index_usernames();
+                StorageVersion::put(IdentityReleases::V2);
+                weight
+            } else {
+                0
+            }
+        }
+
         /// Add a registrar to the system.
         ///
         /// The dispatch origin for this call must be `T::RegistrarOrigin`.
@@ -574,6 +612,10 @@ decl_module! {
         /// If the account already has identity information, the deposit is taken as part payment
         /// for the new deposit.
         ///
+        /// A display name of `Data::Raw` becomes the username of the account, so a new one must
+        /// not be the username of another account, ignoring case. Identities that shared a
+        /// display name before usernames existed keep it, without owning the username.
+        ///
         /// The dispatch origin for this call must be _Signed_.
         ///
         /// - `info`: The identity information.
@@ -586,19 +628,29 @@ decl_module! {
         ///   - where `R` judgements-count (registrar-count-bounded)
         /// - One balance reserve operation.
         /// - One storage mutation (codec-read `O(X' + R)`, codec-write `O(X + R)`).
+        /// - Two username reads and up to two username writes.
         /// - One event.
         /// # </weight>
         #[weight =  T::WeightInfo::set_identity(
             T::MaxRegistrars::get().into(), // R
             T::MaxAdditionalFields::get().into(), // X
-        )]
+        ).saturating_add(T::DbWeight::get().reads_writes(2, 2))]
         fn set_identity(origin, info: IdentityInfo) -> DispatchResultWithPostInfo {
             let sender = ensure_signed(origin)?;
             let extra_fields = info.additional.len() as u32;
             ensure!(extra_fields <= T::MaxAdditionalFields::get(), Error::<T>::TooManyFields);
             let fd = <BalanceOf<T>>::from(extra_fields) * T::FieldDeposit::get();
 
-            let mut id = match <IdentityOf<T>>::get(&sender) {
+            let old_identity = <IdentityOf<T>>::get(&sender);
+            let old_info = old_identity.as_ref().map(|id| id.info.clone());
+            let username = Self::username_of(&info);
+            let renamed = old_info.as_ref().and_then(Self::username_of) != username;
+            if let Some(username) = username.as_ref().filter(|_| renamed) {
+                let owner = Self::username_owner(username);
+                ensure!(owner.map_or(true, |owner| owner == sender), Error::<T>::UsernameTaken);
+            }
+
+            let mut id = match old_identity {
                 Some(mut id) => {
                     // Only keep non-positive judgements.
                     id.judgements.retain(|j| j.1.is_sticky());
@@ -617,6 +669,15 @@ decl_module! {
                 let _ = T::Currency::unreserve(&sender, old_deposit - id.deposit);
             }
 
+            if renamed {
+                if let Some(old_info) = &old_info {
+                    Self::remove_username(&sender, old_info);
+                }
+                if let Some(username) = username {
+                    Usernames::<T>::insert(username, &sender);
+                }
+            }
+
             let judgements = id.judgements.len();
             <IdentityOf<T>>::insert(&sender, id);
             Self::deposit_event(RawEvent::IdentitySet(sender));
@@ -624,7 +685,7 @@ decl_module! {
             Ok(Some(T::WeightInfo::set_identity(
                 judgements as u32, // R
                 extra_fields // X
-            )).into())
+            ).saturating_add(T::DbWeight::get().reads_writes(2, 2))).into())
         }
 
         /// Set the sub-accounts of the sender.
@@ -728,6 +789,7 @@ decl_module! {
             for sub in sub_ids.iter() {
                 <SuperOf<T>>::remove(sub);
             }
+            Self::remove_username(&sender, &id.info);
 
             let _ = T::Currency::unreserve(&sender, deposit.clone());
 
@@ -1036,6 +1098,7 @@ decl_module! {
             for sub in sub_ids.iter() {
                 <SuperOf<T>>::remove(sub);
             }
+            Self::remove_username(&target, &id.info);
             // Slash their deposit from them.
             T::Slashed::on_unbalanced(T::Currency::slash_reserved(&target, deposit).0);
 
@@ -1150,4 +1213,93 @@ impl<T: Trait> Module<T> {
             .filter_map(|a| SuperOf::<T>::get(&a).map(|x| (a, x.1)))
             .collect()
     }
+
+    /// The account whose username is `name`, ignoring case and a leading `@`, if a registrar
+    /// has judged its identity `Reasonable` or `KnownGood`.
+    ///
+    /// A username is released as soon as its identity is renamed, cleared or killed, and anyone
+    /// may then claim it. An extrinsic naming `@name` pays whoever holds the name when it is
+    /// included, not when it was signed, so a new holder judged in between receives it. Wallets
+    /// should show the account a name resolves to and sign with that account id instead.
+    pub fn lookup_username(name: &[u8]) -> Option<T::AccountId> {
+        let name = match name.split_first() {
+            Some((b'@', name)) => name,
+            _ => name,
+        };
+        Self::username_owner(name.to_ascii_lowercase()).filter(Self::has_good_judgement)
+    }
+
+    /// Whether a registrar has judged the identity of `who` `Reasonable` or `KnownGood`.
+    pub fn has_good_judgement(who: &T::AccountId) -> bool {
+        Self::identity(who).map_or(false, |id| {
+            id.judgements
+                .iter()
+                .any(|(_, judgement)| matches!(judgement, Judgement::Reasonable | Judgement::KnownGood))
+        })
+    }
+
+    /// The username `info` claims: its display name in lower case, if stored raw.
+    fn username_of(info: &IdentityInfo) -> Option<Vec<u8>> {
+        match &info.display {
+            Data::Raw(name) if !name.is_empty() => Some(name.to_ascii_lowercase()),
+            _ => None,
+        }
+    }
+
+    /// Release the username of `who`, whose identity `info` was removed.
+    fn remove_username(who: &T::AccountId, info: &IdentityInfo) {
+        if let Some(username) = Self::username_of(info) {
+            if Self::username_owner(&username).as_ref() == Some(who) {
+                Usernames::<T>::remove(username);
+            }
+        }
+    }
+
+    /// Index the usernames of identities set before usernames existed. Where several identities
+    /// share a display name, only the first one found gets it as username.
+    fn index_usernames() -> Weight {
+        let mut identities = 0;
+        for (who, id) in IdentityOf::<T>::iter() {
+            identities += 1;
+            if let Some(username) = Self::username_of(&id.info) {
+                if !Usernames::<T>::contains_key(&username) {
+                    Usernames::<T>::insert(username, who);
+                }
+            }
+        }
+
+        T::DbWeight::get().reads_writes(2 * identities + 1, identities + 1)
+    }
+
+    /// Whether `who` is the account of one of the registrars.
+    pub fn is_registrar(who: &T::AccountId) -> bool {
+        Self::registrars()
+            .iter()
+            .any(|r| r.as_ref().map_or(false, |r| &r.account == who))
+    }
+}
+
+/// Ensure the origin is signed by one of the registrars, so other pallets can hand them
+/// moderation rights. Yields the registrar's account.
+pub struct EnsureRegistrar<T>(PhantomData<T>);
+
+impl<T: Trait> EnsureOrigin<T::Origin> for EnsureRegistrar<T> {
+    type Success = T::AccountId;
+
+    fn try_origin(o: T::Origin) -> Result<Self::Success, T::Origin> {
+        o.into().and_then(|o| match o {
+            RawOrigin::Signed(who) if Module::<T>::is_registrar(&who) => Ok(who),
+            r => Err(T::Origin::from(r)),
+        })
+    }
+
+    #[cfg(feature = "runtime-benchmarks")]
+    fn successful_origin() -> T::Origin {
+        let registrar = Module::<T>::registrars()
+            .into_iter()
+            .filter_map(|r| r.map(|r| r.account))
+            .next()
+            .expect("a registrar must be set up to benchmark with `EnsureRegistrar`");
+        T::Origin::from(RawOrigin::Signed(registrar))
+    }
 }
@@ -546,3 +546,108 @@ fn setting_account_id_should_work() {
         assert_ok!(Identity::set_account_id(Origin::signed(4), 0, 3));
     });
 }
+
+#[test]
+fn ensure_registrar_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(EnsureRegistrar::<Test>::ensure_origin(Origin::signed(3)), BadOrigin);
+        assert_ok!(Identity::add_registrar(Origin::signed(1), 3));
+        assert_eq!(EnsureRegistrar::<Test>::ensure_origin(Origin::signed(3)), Ok(3));
+        assert_noop!(EnsureRegistrar::<Test>::ensure_origin(Origin::signed(4)), BadOrigin);
+        assert_noop!(EnsureRegistrar::<Test>::ensure_origin(Origin::root()), BadOrigin);
+    });
+}
+
+#[test]
+fn usernames_should_be_unique() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+        assert_eq!(Identity::username_owner(b"ten".to_vec()), Some(10));
+        assert_eq!(Identity::username_owner(b"twenty".to_vec()), None);
+
+        // Taken by another account, ignoring case
+        let mut info = twenty();
+        info.display = Data::Raw(b"TEN".to_vec());
+        assert_noop!(
+            Identity::set_identity(Origin::signed(20), info),
+            Error::<Test>::UsernameTaken
+        );
+
+        // The owner can keep it, or rename
+        assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+        assert_ok!(Identity::set_identity(Origin::signed(10), twenty()));
+        assert_eq!(Identity::username_owner(b"ten".to_vec()), None);
+        assert_eq!(Identity::username_owner(b"twenty".to_vec()), Some(10));
+
+        // Only raw display names are usernames
+        let mut info = ten();
+        info.display = Data::BlakeTwo256([0; 32]);
+        assert_ok!(Identity::set_identity(Origin::signed(10), info));
+        assert_eq!(Identity::username_owner(b"twenty".to_vec()), None);
+
+        // Released when the identity goes
+        assert_ok!(Identity::set_identity(Origin::signed(20), twenty()));
+        assert_ok!(Identity::clear_identity(Origin::signed(20)));
+        assert_eq!(Identity::username_owner(b"twenty".to_vec()), None);
+        assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+        assert_ok!(Identity::kill_identity(Origin::signed(2), 10));
+        assert_eq!(Identity::username_owner(b"ten".to_vec()), None);
+    });
+}
+
+#[test]
+fn shared_display_names_should_keep_their_username_owner() {
+    new_test_ext().execute_with(|| {
+        // as `index_usernames` leaves two identities that shared a display name before usernames
+        assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+        assert_ok!(Identity::set_identity(Origin::signed(20), twenty()));
+        IdentityOf::<Test>::mutate(20, |id| id.as_mut().unwrap().info = ten());
+        Usernames::<Test>::remove(b"twenty".to_vec());
+
+        // 20 can keep its display name, but doesn't get the username
+        assert_ok!(Identity::set_identity(Origin::signed(20), ten()));
+        assert_eq!(Identity::username_owner(b"ten".to_vec()), Some(10));
+
+        // Renaming or clearing doesn't release the username of 10
+        assert_ok!(Identity::set_identity(Origin::signed(20), twenty()));
+        assert_eq!(Identity::username_owner(b"ten".to_vec()), Some(10));
+        assert_eq!(Identity::username_owner(b"twenty".to_vec()), Some(20));
+        assert_noop!(
+            Identity::set_identity(Origin::signed(20), ten()),
+            Error::<Test>::UsernameTaken
+        );
+
+        IdentityOf::<Test>::mutate(20, |id| id.as_mut().unwrap().info = ten());
+        Usernames::<Test>::remove(b"twenty".to_vec());
+        assert_ok!(Identity::clear_identity(Origin::signed(20)));
+        assert_eq!(Identity::username_owner(b"ten".to_vec()), Some(10));
+    });
+}
+
+#[test]
+fn usernames_should_resolve_once_judged() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Identity::add_registrar(Origin::signed(1), 3));
+        assert_ok!(Identity::set_identity(Origin::signed(10), ten()));
+        assert_eq!(Identity::lookup_username(b"ten"), None);
+
+        assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::OutOfDate));
+        assert_eq!(Identity::lookup_username(b"ten"), None);
+
+        assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::Reasonable));
+        assert_eq!(Identity::lookup_username(b"ten"), Some(10));
+        assert_eq!(Identity::lookup_username(b"@Ten"), Some(10));
+
+        // Renaming drops the judgement, so the new name waits for another one
+        assert_ok!(Identity::set_identity(Origin::signed(10), twenty()));
+        assert_eq!(Identity::lookup_username(b"twenty"), None);
+        assert_ok!(Identity::provide_judgement(Origin::signed(3), 0, 10, Judgement::KnownGood));
+        assert_eq!(Identity::lookup_username(b"twenty"), Some(10));
+
+        // A released name doesn't resolve to whoever claims it next until they are judged
+        assert_ok!(Identity::clear_identity(Origin::signed(10)));
+        assert_ok!(Identity::set_identity(Origin::signed(20), twenty()));
+        assert_eq!(Identity::username_owner(b"twenty".to_vec()), Some(20));
+        assert_eq!(Identity::lookup_username(b"twenty"), None);
+    });
+}
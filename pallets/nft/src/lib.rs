@@ -14,22 +14,46 @@
 //!
 //! - `create_class` - Create NFT(non fungible token) class
 //! - `transfer` - Transfer NFT(non fungible token) to another account.
+//! - `nest` - Nest NFT(non fungible token) in another NFT
 //! - `mint` - Mint NFT(non fungible token)
 //! - `burn` - Burn NFT(non fungible token)
 //! - `destroy_class` - Destroy NFT(non fungible token) class
+//!
+//! A token can be owned by another token rather than by an account, e.g. gear inside a bag. Nested
+//! tokens move with the token they are nested in, and belong to the account at the root of the
+//! ownership chain; `is_owner` and `TokensByOwner` resolve to that account. Nesting is limited to
+//! `MaxNestingDepth` levels and a token can't be nested in itself or its descendants.
+//!
+//! Other pallets can react to token movements through the `OnTransfer`, `OnMint` and `OnBurn`
+//! hooks, which run in the same storage transaction as the operation itself.
+//!
+//! The same operations are available through the [`NonFungibles`](./traits/trait.NonFungibles.html)
+//! trait family, so other pallets can depend on the traits rather than on this module.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use codec::{Decode, Encode};
-use frame_support::{decl_error, decl_module, decl_storage, ensure, Parameter};
+use frame_support::{
+	decl_error, decl_module, decl_storage, ensure,
+	traits::Get,
+	transactional,
+	weights::Weight,
+	IterableStorageDoubleMap, Parameter,
+};
 use sp_runtime::{
-	traits::{AtLeast32BitUnsigned, CheckedAdd, CheckedSub, Member, One, Zero},
+	traits::{AtLeast32BitUnsigned, CheckedAdd, CheckedSub, MaybeSerializeDeserialize, Member, One, Zero},
 	DispatchError, DispatchResult, RuntimeDebug,
 };
-use sp_std::vec::Vec;
+use sp_std::{cell::Cell, iter::once, vec::Vec};
 
 mod mock;
 mod tests;
+pub mod traits;
+
+pub use traits::{
+	BurnNonFungibles, InspectNonFungibles, MintNonFungibles, NonFungibles, OnNftBurn, OnNftMint, OnNftTransfer,
+	TransferNonFungibles,
+};
 
 pub type CID = Vec<u8>;
 
@@ -46,26 +70,68 @@ pub struct ClassInfo<TokenId, AccountId, Data> {
 	pub data: Data,
 }
 
+/// Token owner
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub enum TokenOwner<AccountId, ClassId, TokenId> {
+	/// Owned directly by an account
+	Account(AccountId),
+	/// Nested in another token, and so owned by the root owner of that token
+	Token(ClassId, TokenId),
+}
+
 /// Token info
 #[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
-pub struct TokenInfo<AccountId, Data> {
+pub struct TokenInfo<AccountId, ClassId, TokenId, Data> {
 	/// Token metadata
 	pub metadata: Vec<u8>,
 	/// Token owner
-	pub owner: AccountId,
+	pub owner: TokenOwner<AccountId, ClassId, TokenId>,
 	/// Token Properties
 	pub data: Data,
 }
 
+/// Token info before tokens could be nested, kept to migrate old storage
+#[derive(Decode)]
+struct TokenInfoV1<AccountId, Data> {
+	metadata: Vec<u8>,
+	owner: AccountId,
+	data: Data,
+}
+
+/// Storage layout version, named apart from the `Releases` of other pallets as polkadot.js
+/// knows types by name only
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+pub enum NftReleases {
+	/// Tokens are owned by accounts
+	V1,
+	/// Tokens are owned by accounts or by other tokens
+	V2,
+}
+
+impl Default for NftReleases {
+	fn default() -> Self {
+		NftReleases::V1
+	}
+}
+
 pub trait Trait: frame_system::Trait {
 	/// The class ID type
 	type ClassId: Parameter + Member + AtLeast32BitUnsigned + Default + Copy;
 	/// The token ID type
 	type TokenId: Parameter + Member + AtLeast32BitUnsigned + Default + Copy;
 	/// The class properties type
-	type ClassData: Parameter + Member;
+	type ClassData: Parameter + Member + MaybeSerializeDeserialize;
 	/// The token properties type
-	type TokenData: Parameter + Member;
+	type TokenData: Parameter + Member + MaybeSerializeDeserialize;
+	/// The maximum number of tokens a token can be nested in
+	type MaxNestingDepth: Get<u32>;
+	/// Called after a token changes owner, in the same storage transaction. When a token
+	/// carries nested tokens with it, this is called for each of them as well
+	type OnTransfer: OnNftTransfer<Self::AccountId, Self::ClassId, Self::TokenId>;
+	/// Called after a token is minted, in the same storage transaction
+	type OnMint: OnNftMint<Self::AccountId, Self::ClassId, Self::TokenId>;
+	/// Called after a token is burned, in the same storage transaction
+	type OnBurn: OnNftBurn<Self::AccountId, Self::ClassId, Self::TokenId>;
 }
 
 decl_error! {
@@ -86,12 +152,29 @@ decl_error! {
 		/// Can not destroy class
 		/// Total issuance is not 0
 		CannotDestroyClass,
+		/// A token can't be nested in itself or in a token nested in it
+		CannotNestInDescendant,
+		/// Nesting would exceed `MaxNestingDepth`
+		TooDeeplyNested,
+		/// Can not burn a token that other tokens are nested in
+		CannotBurnWithNestedTokens,
 	}
 }
 
 pub type ClassInfoOf<T> =
 	ClassInfo<<T as Trait>::TokenId, <T as frame_system::Trait>::AccountId, <T as Trait>::ClassData>;
-pub type TokenInfoOf<T> = TokenInfo<<T as frame_system::Trait>::AccountId, <T as Trait>::TokenData>;
+pub type TokenInfoOf<T> = TokenInfo<
+	<T as frame_system::Trait>::AccountId,
+	<T as Trait>::ClassId,
+	<T as Trait>::TokenId,
+	<T as Trait>::TokenData,
+>;
+pub type TokenOwnerOf<T> = TokenOwner<<T as frame_system::Trait>::AccountId, <T as Trait>::ClassId, <T as Trait>::TokenId>;
+/// A token minted at genesis: owner, metadata and data.
+pub type GenesisTokenData<AccountId, TokenData> = (AccountId, Vec<u8>, TokenData);
+/// A class created at genesis: owner, metadata, data and the tokens minted in it.
+pub type GenesisTokens<AccountId, ClassData, TokenData> =
+	(AccountId, Vec<u8>, ClassData, Vec<GenesisTokenData<AccountId, TokenData>>);
 
 decl_storage! {
 	trait Store for Module<T: Trait> as NonFungibleToken {
@@ -108,13 +191,38 @@ decl_storage! {
 		/// Returns `None` if token info not set or removed.
 		pub Tokens get(fn tokens): double_map hasher(twox_64_concat) T::ClassId, hasher(twox_64_concat) T::TokenId => Option<TokenInfoOf<T>>;
 		/// Token existence check by owner and class ID.
+		///
+		/// Nested tokens are indexed under their root owner.
 		#[cfg(not(feature = "disable-tokens-by-owner"))]
 		pub TokensByOwner get(fn tokens_by_owner): double_map hasher(twox_64_concat) T::AccountId, hasher(twox_64_concat) (T::ClassId, T::TokenId) => Option<()>;
+		/// Tokens nested directly in a token, by parent token.
+		pub Children get(fn children): double_map hasher(twox_64_concat) (T::ClassId, T::TokenId), hasher(twox_64_concat) (T::ClassId, T::TokenId) => Option<()>;
+		/// Storage layout version, used to migrate storage on runtime upgrades.
+		pub StorageVersion get(fn storage_version) build(|_| NftReleases::V2): NftReleases;
+	}
+	add_extra_genesis {
+		config(tokens): Vec<GenesisTokens<T::AccountId, T::ClassData, T::TokenData>>;
+
+		build(|config: &GenesisConfig<T>| {
+			for (owner, metadata, data, tokens) in &config.tokens {
+				let class_id = Module::<T>::create_class(owner, metadata.clone(), data.clone())
+					.expect("Create class cannot fail while building genesis");
+				for (token_owner, token_metadata, token_data) in tokens {
+					Module::<T>::mint(token_owner, class_id, token_metadata.clone(), token_data.clone())
+						.expect("Token mint cannot fail during genesis");
+				}
+			}
+		})
 	}
 }
 
 decl_module! {
 	pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+		const MaxNestingDepth: u32 = T::MaxNestingDepth::get();
+
+		fn on_runtime_upgrade() -> Weight {
+			Self::migrate_to_nested_owners()
+		}
 	}
 }
 
@@ -143,28 +251,68 @@ impl<T: Trait> Module<T> {
 	}
 
 	/// Transfer NFT(non fungible token) from `from` account to `to` account
+	///
+	/// Tokens nested in the token move with it. A nested token can be transferred by its root
+	/// owner, which takes it out of its parent.
+	#[transactional]
 	pub fn transfer(from: &T::AccountId, to: &T::AccountId, token: (T::ClassId, T::TokenId)) -> DispatchResult {
-		Tokens::<T>::try_mutate(token.0, token.1, |token_info| -> DispatchResult {
-			let mut info = token_info.as_mut().ok_or(Error::<T>::TokenNotFound)?;
-			ensure!(info.owner == *from, Error::<T>::NoPermission);
-			if from == to {
-				// no change needed
-				return Ok(());
+		let (parents, root_owner) = Self::ancestors(token)?;
+		ensure!(root_owner == *from, Error::<T>::NoPermission);
+		if from == to && parents.is_empty() {
+			// no change needed
+			return Ok(());
+		}
+
+		if let Some(parent) = parents.first() {
+			Children::<T>::remove(parent, token);
+		}
+		Tokens::<T>::mutate(token.0, token.1, |token_info| {
+			if let Some(info) = token_info {
+				info.owner = TokenOwner::Account(to.clone());
 			}
+		});
 
-			info.owner = to.clone();
+		Self::move_nested(from, to, token)
+	}
 
-			#[cfg(not(feature = "disable-tokens-by-owner"))]
-			{
-				TokensByOwner::<T>::remove(from, token);
-				TokensByOwner::<T>::insert(to, token, ());
+	/// Nest NFT(non fungible token) `token` in `parent`
+	///
+	/// `from` must be the root owner of both `token` and `parent`.
+	#[transactional]
+	pub fn nest(
+		from: &T::AccountId,
+		token: (T::ClassId, T::TokenId),
+		parent: (T::ClassId, T::TokenId),
+	) -> DispatchResult {
+		let (parents, root_owner) = Self::ancestors(token)?;
+		ensure!(root_owner == *from, Error::<T>::NoPermission);
+
+		let (parent_ancestors, parent_owner) = Self::ancestors(parent)?;
+		ensure!(parent_owner == *from, Error::<T>::NoPermission);
+		ensure!(
+			token != parent && !parent_ancestors.contains(&token),
+			Error::<T>::CannotNestInDescendant
+		);
+
+		let (_, height) = Self::descendants(token);
+		let depth = (parent_ancestors.len() as u32).saturating_add(1).saturating_add(height);
+		ensure!(depth <= T::MaxNestingDepth::get(), Error::<T>::TooDeeplyNested);
+
+		if let Some(old_parent) = parents.first() {
+			Children::<T>::remove(old_parent, token);
+		}
+		Children::<T>::insert(parent, token, ());
+		Tokens::<T>::mutate(token.0, token.1, |token_info| {
+			if let Some(info) = token_info {
+				info.owner = TokenOwner::Token(parent.0, parent.1);
 			}
+		});
 
-			Ok(())
-		})
+		Self::move_nested(from, from, token)
 	}
 
 	/// Mint NFT(non fungible token) to `owner`
+	#[transactional]
 	pub fn mint(
 		owner: &T::AccountId,
 		class_id: T::ClassId,
@@ -186,37 +334,49 @@ impl<T: Trait> Module<T> {
 
 			let token_info = TokenInfo {
 				metadata,
-				owner: owner.clone(),
+				owner: TokenOwner::Account(owner.clone()),
 				data,
 			};
 			Tokens::<T>::insert(class_id, token_id, token_info);
 			#[cfg(not(feature = "disable-tokens-by-owner"))]
 			TokensByOwner::<T>::insert(owner, (class_id, token_id), ());
 
+			T::OnMint::on_mint(owner, (class_id, token_id))?;
+
 			Ok(token_id)
 		})
 	}
 
 	/// Burn NFT(non fungible token) from `owner`
+	///
+	/// Tokens nested in the token must be taken out of it first.
+	#[transactional]
 	pub fn burn(owner: &T::AccountId, token: (T::ClassId, T::TokenId)) -> DispatchResult {
-		Tokens::<T>::try_mutate_exists(token.0, token.1, |token_info| -> DispatchResult {
-			let t = token_info.take().ok_or(Error::<T>::TokenNotFound)?;
-			ensure!(t.owner == *owner, Error::<T>::NoPermission);
-
-			Classes::<T>::try_mutate(token.0, |class_info| -> DispatchResult {
-				let info = class_info.as_mut().ok_or(Error::<T>::ClassNotFound)?;
-				info.total_issuance = info
-					.total_issuance
-					.checked_sub(&One::one())
-					.ok_or(Error::<T>::NumOverflow)?;
-				Ok(())
-			})?;
+		let (parents, root_owner) = Self::ancestors(token)?;
+		ensure!(root_owner == *owner, Error::<T>::NoPermission);
+		ensure!(
+			Children::<T>::iter_prefix(token).next().is_none(),
+			Error::<T>::CannotBurnWithNestedTokens
+		);
+
+		Tokens::<T>::remove(token.0, token.1);
+		if let Some(parent) = parents.first() {
+			Children::<T>::remove(parent, token);
+		}
+
+		Classes::<T>::try_mutate(token.0, |class_info| -> DispatchResult {
+			let info = class_info.as_mut().ok_or(Error::<T>::ClassNotFound)?;
+			info.total_issuance = info
+				.total_issuance
+				.checked_sub(&One::one())
+				.ok_or(Error::<T>::NumOverflow)?;
+			Ok(())
+		})?;
 
-			#[cfg(not(feature = "disable-tokens-by-owner"))]
-			TokensByOwner::<T>::remove(owner, token);
+		#[cfg(not(feature = "disable-tokens-by-owner"))]
+		TokensByOwner::<T>::remove(owner, token);
 
-			Ok(())
-		})
+		T::OnBurn::on_burn(owner, token)
 	}
 
 	/// Destroy NFT(non fungible token) class
@@ -232,11 +392,158 @@ impl<T: Trait> Module<T> {
 		})
 	}
 
+	/// Whether `account` is the root owner of `token`
 	pub fn is_owner(account: &T::AccountId, token: (T::ClassId, T::TokenId)) -> bool {
 		#[cfg(feature = "disable-tokens-by-owner")]
-		return Tokens::<T>::get(token.0, token.1).map_or(false, |token| token.owner == *account);
+		return Self::root_owner(token).map_or(false, |owner| owner == *account);
 
 		#[cfg(not(feature = "disable-tokens-by-owner"))]
 		TokensByOwner::<T>::contains_key(account, token)
 	}
-}
\ No newline at end of file
+
+	/// The account at the root of `token`'s ownership chain
+	pub fn root_owner(token: (T::ClassId, T::TokenId)) -> Option<T::AccountId> {
+		Self::ancestors(token).ok().map(|(_, owner)| owner)
+	}
+
+	/// The tokens `token` is nested in, innermost first, and the account owning the outermost one
+	fn ancestors(
+		token: (T::ClassId, T::TokenId),
+	) -> Result<(Vec<(T::ClassId, T::TokenId)>, T::AccountId), DispatchError> {
+		let mut ancestors = Vec::new();
+		let mut owner = Tokens::<T>::get(token.0, token.1)
+			.ok_or(Error::<T>::TokenNotFound)?
+			.owner;
+
+		loop {
+			match owner {
+				TokenOwner::Account(account) => return Ok((ancestors, account)),
+				TokenOwner::Token(class_id, token_id) => {
+					// nesting never exceeds the limit, so this also guards against cycles
+					ensure!(
+						ancestors.len() < T::MaxNestingDepth::get() as usize,
+						Error::<T>::TooDeeplyNested
+					);
+					ancestors.push((class_id, token_id));
+					owner = Tokens::<T>::get(class_id, token_id)
+						.ok_or(Error::<T>::TokenNotFound)?
+						.owner;
+				}
+			}
+		}
+	}
+
+	/// All tokens nested in `token` at any depth, and the number of levels they span
+	fn descendants(token: (T::ClassId, T::TokenId)) -> (Vec<(T::ClassId, T::TokenId)>, u32) {
+		let mut descendants = Vec::new();
+		let mut height = 0;
+		let mut level: Vec<_> = once(token).collect();
+
+		while height < T::MaxNestingDepth::get() {
+			level = level
+				.iter()
+				.flat_map(|parent| Children::<T>::iter_prefix(parent).map(|(child, _)| child))
+				.collect();
+			if level.is_empty() {
+				break;
+			}
+			height += 1;
+			descendants.extend_from_slice(&level);
+		}
+
+		(descendants, height)
+	}
+
+	/// Move `token` and the tokens nested in it from `from` to `to` in `TokensByOwner`, calling
+	/// `OnTransfer` for each of them even if the owner stays the same
+	fn move_nested(from: &T::AccountId, to: &T::AccountId, token: (T::ClassId, T::TokenId)) -> DispatchResult {
+		let (descendants, _) = Self::descendants(token);
+		for token in once(token).chain(descendants) {
+			#[cfg(not(feature = "disable-tokens-by-owner"))]
+			{
+				if from != to {
+					TokensByOwner::<T>::remove(from, token);
+					TokensByOwner::<T>::insert(to, token, ());
+				}
+			}
+
+			T::OnTransfer::on_transfer(from, to, token)?;
+		}
+
+		Ok(())
+	}
+
+	/// Wrap the owner of tokens stored before nesting was supported in `TokenOwner::Account`
+	fn migrate_to_nested_owners() -> Weight {
+		if StorageVersion::get() != NftReleases::V1 {
+			return 0;
+		}
+
+		let migrated = Cell::new(0 as Weight);
+		Tokens::<T>::translate::<TokenInfoV1<T::AccountId, T::TokenData>, _>(|_, _, old| {
+			migrated.set(migrated.get().saturating_add(1));
+			Some(TokenInfo {
+				metadata: old.metadata,
+				owner: TokenOwner::Account(old.owner),
+				data: old.data,
+			})
+		});
+		StorageVersion::put(NftReleases::V2);
+
+		T::DbWeight::get().reads_writes(migrated.get().saturating_add(1), migrated.get().saturating_add(1))
+	}
+}
+
+impl<T: Trait> InspectNonFungibles<T::AccountId> for Module<T> {
+	type ClassId = T::ClassId;
+	type TokenId = T::TokenId;
+	type ClassData = T::ClassData;
+	type TokenData = T::TokenData;
+
+	fn class_owner(class_id: T::ClassId) -> Option<T::AccountId> {
+		Classes::<T>::get(class_id).map(|info| info.owner)
+	}
+
+	fn owner(token: (T::ClassId, T::TokenId)) -> Option<T::AccountId> {
+		Self::root_owner(token)
+	}
+
+	fn is_owner(account: &T::AccountId, token: (T::ClassId, T::TokenId)) -> bool {
+		Self::is_owner(account, token)
+	}
+}
+
+impl<T: Trait> TransferNonFungibles<T::AccountId> for Module<T> {
+	fn transfer(from: &T::AccountId, to: &T::AccountId, token: (T::ClassId, T::TokenId)) -> DispatchResult {
+		Self::transfer(from, to, token)
+	}
+
+	fn nest(from: &T::AccountId, token: (T::ClassId, T::TokenId), parent: (T::ClassId, T::TokenId)) -> DispatchResult {
+		Self::nest(from, token, parent)
+	}
+}
+
+impl<T: Trait> MintNonFungibles<T::AccountId> for Module<T> {
+	fn create_class(owner: &T::AccountId, metadata: Vec<u8>, data: T::ClassData) -> Result<T::ClassId, DispatchError> {
+		Self::create_class(owner, metadata, data)
+	}
+
+	fn mint(
+		owner: &T::AccountId,
+		class_id: T::ClassId,
+		metadata: Vec<u8>,
+		data: T::TokenData,
+	) -> Result<T::TokenId, DispatchError> {
+		Self::mint(owner, class_id, metadata, data)
+	}
+}
+
+impl<T: Trait> BurnNonFungibles<T::AccountId> for Module<T> {
+	fn burn(owner: &T::AccountId, token: (T::ClassId, T::TokenId)) -> DispatchResult {
+		Self::burn(owner, token)
+	}
+
+	fn destroy_class(owner: &T::AccountId, class_id: T::ClassId) -> DispatchResult {
+		Self::destroy_class(owner, class_id)
+	}
+}
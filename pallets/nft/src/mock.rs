@@ -54,11 +54,19 @@ impl frame_system::Trait for Runtime {
 }
 pub type System = frame_system::Module<Runtime>;
 
+parameter_types! {
+	pub const MaxNestingDepth: u32 = 2;
+}
+
 impl Trait for Runtime {
 	type ClassId = u64;
 	type TokenId = u64;
 	type ClassData = ();
 	type TokenData = ();
+	type MaxNestingDepth = MaxNestingDepth;
+	type OnTransfer = ();
+	type OnMint = ();
+	type OnBurn = ();
 }
 pub type NonFungibleTokenModule = Module<Runtime>;
 
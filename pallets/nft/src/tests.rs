@@ -3,9 +3,9 @@
 #![cfg(test)]
 
 use super::*;
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{assert_noop, assert_ok, traits::OnRuntimeUpgrade};
 use mock::{
-	ExtBuilder, NonFungibleTokenModule, Runtime, ALICE, BOB, CLASS_ID, CLASS_ID_NOT_EXIST, TOKEN_ID, TOKEN_ID_NOT_EXIST,
+	AccountId, ExtBuilder, NonFungibleTokenModule, Runtime, ALICE, BOB, CLASS_ID, CLASS_ID_NOT_EXIST, TOKEN_ID, TOKEN_ID_NOT_EXIST,
 };
 
 #[test]
@@ -179,4 +179,166 @@ fn destroy_class_should_fail() {
 		assert_ok!(NonFungibleTokenModule::destroy_class(&ALICE, CLASS_ID));
 		assert_eq!(Classes::<Runtime>::contains_key(CLASS_ID), false);
 	});
-}
\ No newline at end of file
+}
+
+#[test]
+fn nest_should_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(NonFungibleTokenModule::create_class(&ALICE, vec![1], ()));
+		let bag = (CLASS_ID, NonFungibleTokenModule::mint(&ALICE, CLASS_ID, vec![1], ()).unwrap());
+		let sword = (CLASS_ID, NonFungibleTokenModule::mint(&ALICE, CLASS_ID, vec![1], ()).unwrap());
+		let gem = (CLASS_ID, NonFungibleTokenModule::mint(&BOB, CLASS_ID, vec![1], ()).unwrap());
+
+		assert_ok!(NonFungibleTokenModule::nest(&ALICE, sword, bag));
+		assert_eq!(
+			NonFungibleTokenModule::tokens(sword.0, sword.1).unwrap().owner,
+			TokenOwner::Token(bag.0, bag.1)
+		);
+		assert_eq!(NonFungibleTokenModule::children(bag, sword), Some(()));
+		assert!(NonFungibleTokenModule::is_owner(&ALICE, sword));
+
+		// nested tokens move with their parent
+		assert_ok!(NonFungibleTokenModule::transfer(&ALICE, &BOB, bag));
+		assert!(NonFungibleTokenModule::is_owner(&BOB, sword));
+		assert!(!NonFungibleTokenModule::is_owner(&ALICE, sword));
+		assert_eq!(NonFungibleTokenModule::root_owner(sword), Some(BOB));
+
+		assert_ok!(NonFungibleTokenModule::nest(&BOB, gem, sword));
+		assert!(NonFungibleTokenModule::is_owner(&BOB, gem));
+
+		// transferring a nested token takes it out of its parent
+		assert_ok!(NonFungibleTokenModule::transfer(&BOB, &ALICE, sword));
+		assert_eq!(
+			NonFungibleTokenModule::tokens(sword.0, sword.1).unwrap().owner,
+			TokenOwner::Account(ALICE)
+		);
+		assert_eq!(NonFungibleTokenModule::children(bag, sword), None);
+		assert!(NonFungibleTokenModule::is_owner(&ALICE, gem));
+		assert!(NonFungibleTokenModule::is_owner(&BOB, bag));
+	});
+}
+
+#[test]
+fn nest_should_fail() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(NonFungibleTokenModule::create_class(&ALICE, vec![1], ()));
+		for _ in 0..4 {
+			assert_ok!(NonFungibleTokenModule::mint(&ALICE, CLASS_ID, vec![1], ()));
+		}
+		assert_ok!(NonFungibleTokenModule::nest(&ALICE, (CLASS_ID, 1), (CLASS_ID, 0)));
+		assert_ok!(NonFungibleTokenModule::nest(&ALICE, (CLASS_ID, 2), (CLASS_ID, 1)));
+
+		assert_noop!(
+			NonFungibleTokenModule::nest(&ALICE, (CLASS_ID, TOKEN_ID_NOT_EXIST), (CLASS_ID, 0)),
+			Error::<Runtime>::TokenNotFound
+		);
+		assert_noop!(
+			NonFungibleTokenModule::nest(&BOB, (CLASS_ID, 3), (CLASS_ID, 0)),
+			Error::<Runtime>::NoPermission
+		);
+		assert_ok!(NonFungibleTokenModule::transfer(&ALICE, &BOB, (CLASS_ID, 3)));
+		assert_noop!(
+			NonFungibleTokenModule::nest(&BOB, (CLASS_ID, 3), (CLASS_ID, 0)),
+			Error::<Runtime>::NoPermission
+		);
+		assert_ok!(NonFungibleTokenModule::transfer(&BOB, &ALICE, (CLASS_ID, 3)));
+		assert_noop!(
+			NonFungibleTokenModule::nest(&ALICE, (CLASS_ID, 0), (CLASS_ID, 0)),
+			Error::<Runtime>::CannotNestInDescendant
+		);
+		assert_noop!(
+			NonFungibleTokenModule::nest(&ALICE, (CLASS_ID, 0), (CLASS_ID, 2)),
+			Error::<Runtime>::CannotNestInDescendant
+		);
+		assert_noop!(
+			NonFungibleTokenModule::nest(&ALICE, (CLASS_ID, 3), (CLASS_ID, 2)),
+			Error::<Runtime>::TooDeeplyNested
+		);
+		assert_noop!(
+			NonFungibleTokenModule::nest(&ALICE, (CLASS_ID, 0), (CLASS_ID, 3)),
+			Error::<Runtime>::TooDeeplyNested
+		);
+		assert_noop!(
+			NonFungibleTokenModule::burn(&ALICE, (CLASS_ID, 1)),
+			Error::<Runtime>::CannotBurnWithNestedTokens
+		);
+	});
+}
+
+#[test]
+fn migrate_to_nested_owners_should_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		#[derive(Encode)]
+		struct OldTokenInfo {
+			metadata: Vec<u8>,
+			owner: AccountId,
+			data: (),
+		}
+		let old = OldTokenInfo {
+			metadata: vec![1],
+			owner: BOB,
+			data: (),
+		};
+		frame_support::storage::unhashed::put(&Tokens::<Runtime>::hashed_key_for(CLASS_ID, TOKEN_ID), &old);
+
+		NonFungibleTokenModule::on_runtime_upgrade();
+		assert_eq!(
+			NonFungibleTokenModule::tokens(CLASS_ID, TOKEN_ID),
+			Some(TokenInfo {
+				metadata: vec![1],
+				owner: TokenOwner::Account(BOB),
+				data: (),
+			})
+		);
+		assert_eq!(StorageVersion::get(), NftReleases::V2);
+	});
+}
+
+#[test]
+fn non_fungibles_traits_should_work() {
+	ExtBuilder::default().build().execute_with(|| {
+		let class_id = <NonFungibleTokenModule as MintNonFungibles<_>>::create_class(&ALICE, vec![1], ()).unwrap();
+		assert_eq!(<NonFungibleTokenModule as InspectNonFungibles<_>>::class_owner(class_id), Some(ALICE));
+
+		let token_id = <NonFungibleTokenModule as MintNonFungibles<_>>::mint(&BOB, class_id, vec![1], ()).unwrap();
+		assert_eq!(<NonFungibleTokenModule as InspectNonFungibles<_>>::owner((class_id, token_id)), Some(BOB));
+
+		assert_ok!(<NonFungibleTokenModule as TransferNonFungibles<_>>::transfer(&BOB, &ALICE, (class_id, token_id)));
+		assert!(<NonFungibleTokenModule as InspectNonFungibles<_>>::is_owner(&ALICE, (class_id, token_id)));
+
+		assert_ok!(<NonFungibleTokenModule as BurnNonFungibles<_>>::burn(&ALICE, (class_id, token_id)));
+		assert_eq!(<NonFungibleTokenModule as InspectNonFungibles<_>>::owner((class_id, token_id)), None);
+
+		assert_ok!(<NonFungibleTokenModule as BurnNonFungibles<_>>::destroy_class(&ALICE, class_id));
+		assert_eq!(<NonFungibleTokenModule as InspectNonFungibles<_>>::class_owner(class_id), None);
+	});
+}
+
+#[test]
+fn genesis_config_should_work() {
+	let mut t = frame_system::GenesisConfig::default()
+		.build_storage::<Runtime>()
+		.unwrap();
+	GenesisConfig::<Runtime> {
+		tokens: vec![
+			(ALICE, vec![1], (), vec![(ALICE, vec![2], ()), (BOB, vec![3], ())]),
+			(BOB, vec![4], (), vec![]),
+		],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	sp_io::TestExternalities::new(t).execute_with(|| {
+		assert_eq!(NonFungibleTokenModule::next_class_id(), 2);
+		assert_eq!(NonFungibleTokenModule::classes(CLASS_ID).unwrap().owner, ALICE);
+		assert_eq!(NonFungibleTokenModule::classes(CLASS_ID).unwrap().total_issuance, 2);
+		assert_eq!(NonFungibleTokenModule::classes(1).unwrap().owner, BOB);
+		assert_eq!(NonFungibleTokenModule::next_token_id(CLASS_ID), 2);
+		assert_eq!(
+			NonFungibleTokenModule::tokens(CLASS_ID, TOKEN_ID).unwrap().metadata,
+			vec![2]
+		);
+		assert!(NonFungibleTokenModule::is_owner(&ALICE, (CLASS_ID, 0)));
+		assert!(NonFungibleTokenModule::is_owner(&BOB, (CLASS_ID, 1)));
+	});
+}
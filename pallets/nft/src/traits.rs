@@ -0,0 +1,125 @@
+//! Traits that let other pallets work with non-fungible tokens without depending on a specific
+//! implementation, in the same way `Currency` abstracts over balances.
+
+use frame_support::Parameter;
+use sp_runtime::{
+	traits::{AtLeast32BitUnsigned, Member},
+	DispatchError, DispatchResult,
+};
+use sp_std::vec::Vec;
+
+/// Read-only access to classes and tokens.
+pub trait InspectNonFungibles<AccountId> {
+	/// The class ID type
+	type ClassId: Parameter + Member + AtLeast32BitUnsigned + Default + Copy;
+	/// The token ID type
+	type TokenId: Parameter + Member + AtLeast32BitUnsigned + Default + Copy;
+	/// The class properties type
+	type ClassData: Parameter + Member;
+	/// The token properties type
+	type TokenData: Parameter + Member;
+
+	/// Owner of `class_id`, or `None` if the class does not exist.
+	fn class_owner(class_id: Self::ClassId) -> Option<AccountId>;
+
+	/// Owner of the token, or `None` if the token does not exist. For a token nested in other
+	/// tokens, this is the account owning the outermost one.
+	fn owner(token: (Self::ClassId, Self::TokenId)) -> Option<AccountId>;
+
+	/// Whether `account` owns the token.
+	fn is_owner(account: &AccountId, token: (Self::ClassId, Self::TokenId)) -> bool;
+}
+
+/// Moving tokens between accounts.
+pub trait TransferNonFungibles<AccountId>: InspectNonFungibles<AccountId> {
+	/// Transfer the token from `from` to `to`. Fails if `from` does not own it.
+	fn transfer(from: &AccountId, to: &AccountId, token: (Self::ClassId, Self::TokenId)) -> DispatchResult;
+
+	/// Nest the token in `parent`, so it moves with `parent` and belongs to its owner. Fails if
+	/// `from` does not own both tokens.
+	fn nest(
+		from: &AccountId,
+		token: (Self::ClassId, Self::TokenId),
+		parent: (Self::ClassId, Self::TokenId),
+	) -> DispatchResult;
+}
+
+/// Creating classes and minting tokens into them.
+pub trait MintNonFungibles<AccountId>: InspectNonFungibles<AccountId> {
+	/// Create a new class owned by `owner` and return its ID.
+	fn create_class(owner: &AccountId, metadata: Vec<u8>, data: Self::ClassData) -> Result<Self::ClassId, DispatchError>;
+
+	/// Mint a token of `class_id` to `owner` and return its ID.
+	fn mint(
+		owner: &AccountId,
+		class_id: Self::ClassId,
+		metadata: Vec<u8>,
+		data: Self::TokenData,
+	) -> Result<Self::TokenId, DispatchError>;
+}
+
+/// Burning tokens and destroying empty classes.
+pub trait BurnNonFungibles<AccountId>: InspectNonFungibles<AccountId> {
+	/// Burn a token owned by `owner`.
+	fn burn(owner: &AccountId, token: (Self::ClassId, Self::TokenId)) -> DispatchResult;
+
+	/// Destroy a class owned by `owner` that has no tokens left.
+	fn destroy_class(owner: &AccountId, class_id: Self::ClassId) -> DispatchResult;
+}
+
+/// The full set of non-fungible operations, implemented for anything that implements each of
+/// the individual traits.
+pub trait NonFungibles<AccountId>:
+	InspectNonFungibles<AccountId>
+	+ TransferNonFungibles<AccountId>
+	+ MintNonFungibles<AccountId>
+	+ BurnNonFungibles<AccountId>
+{
+}
+
+impl<AccountId, T> NonFungibles<AccountId> for T where
+	T: InspectNonFungibles<AccountId>
+		+ TransferNonFungibles<AccountId>
+		+ MintNonFungibles<AccountId>
+		+ BurnNonFungibles<AccountId>
+{
+}
+
+/// Called whenever a token changes owner. Returning an error aborts the transfer.
+pub trait OnNftTransfer<AccountId, ClassId, TokenId> {
+	fn on_transfer(from: &AccountId, to: &AccountId, token: (ClassId, TokenId)) -> DispatchResult;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<AccountId, ClassId: Copy, TokenId: Copy> OnNftTransfer<AccountId, ClassId, TokenId> for Tuple {
+	fn on_transfer(from: &AccountId, to: &AccountId, token: (ClassId, TokenId)) -> DispatchResult {
+		for_tuples!( #( Tuple::on_transfer(from, to, token)?; )* );
+		Ok(())
+	}
+}
+
+/// Called whenever a token is minted. Returning an error aborts the mint.
+pub trait OnNftMint<AccountId, ClassId, TokenId> {
+	fn on_mint(owner: &AccountId, token: (ClassId, TokenId)) -> DispatchResult;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<AccountId, ClassId: Copy, TokenId: Copy> OnNftMint<AccountId, ClassId, TokenId> for Tuple {
+	fn on_mint(owner: &AccountId, token: (ClassId, TokenId)) -> DispatchResult {
+		for_tuples!( #( Tuple::on_mint(owner, token)?; )* );
+		Ok(())
+	}
+}
+
+/// Called whenever a token is burned. Returning an error aborts the burn.
+pub trait OnNftBurn<AccountId, ClassId, TokenId> {
+	fn on_burn(owner: &AccountId, token: (ClassId, TokenId)) -> DispatchResult;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<AccountId, ClassId: Copy, TokenId: Copy> OnNftBurn<AccountId, ClassId, TokenId> for Tuple {
+	fn on_burn(owner: &AccountId, token: (ClassId, TokenId)) -> DispatchResult {
+		for_tuples!( #( Tuple::on_burn(owner, token)?; )* );
+		Ok(())
+	}
+}
@@ -0,0 +1,15 @@
+//! Runtime API definition for the pause pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use sp_std::prelude::*;
+
+sp_api::decl_runtime_apis! {
+    pub trait PauseApi {
+        /// Names of the paused pallets.
+        fn paused_pallets() -> Vec<Vec<u8>>;
+
+        /// The calls paused on their own, as pallet and call names.
+        fn paused_calls() -> Vec<(Vec<u8>, Vec<u8>)>;
+    }
+}
@@ -0,0 +1,111 @@
+//! RPC interface for the pause pallet.
+
+use std::sync::Arc;
+
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+pub use pallet_pause_rpc_runtime_api::PauseApi as PauseRuntimeApi;
+
+/// A call paused on its own.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PausedCall {
+    pub pallet: String,
+    pub call: String,
+}
+
+/// What is currently paused. A call can't be dispatched if its pallet is in `pallets` or the
+/// call itself is in `calls`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PauseState {
+    pub pallets: Vec<String>,
+    pub calls: Vec<PausedCall>,
+}
+
+#[rpc]
+pub trait PauseApi<BlockHash> {
+    /// The pallets and calls paused through the pause pallet, so wallets can tell why a
+    /// transaction would be rejected.
+    #[rpc(name = "loot_pauseState")]
+    fn pause_state(&self, at: Option<BlockHash>) -> Result<PauseState>;
+}
+
+/// A struct that implements the [`PauseApi`].
+pub struct Pause<C, B> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Pause<C, B> {
+    /// Create new `Pause` with the given reference to the client.
+    pub fn new(client: Arc<C>) -> Self {
+        Pause {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+    /// The call to runtime failed.
+    RuntimeError,
+}
+
+impl From<Error> for i64 {
+    fn from(e: Error) -> i64 {
+        match e {
+            Error::RuntimeError => 1,
+        }
+    }
+}
+
+fn runtime_error(message: &str, e: impl std::fmt::Debug) -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(Error::RuntimeError.into()),
+        message: message.into(),
+        data: Some(format!("{:?}", e).into()),
+    }
+}
+
+fn name(bytes: Vec<u8>) -> String {
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+impl<C, Block> PauseApi<<Block as BlockT>::Hash> for Pause<C, Block>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static,
+    C: ProvideRuntimeApi<Block>,
+    C: HeaderBackend<Block>,
+    C::Api: PauseRuntimeApi<Block>,
+{
+    fn pause_state(&self, at: Option<<Block as BlockT>::Hash>) -> Result<PauseState> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        let pallets = api
+            .paused_pallets(&at)
+            .map_err(|e| runtime_error("Unable to query paused pallets.", e))?;
+        let calls = api
+            .paused_calls(&at)
+            .map_err(|e| runtime_error("Unable to query paused calls.", e))?;
+
+        Ok(PauseState {
+            pallets: pallets.into_iter().map(name).collect(),
+            calls: calls
+                .into_iter()
+                .map(|(pallet, call)| PausedCall {
+                    pallet: name(pallet),
+                    call: name(call),
+                })
+                .collect(),
+        })
+    }
+}
@@ -0,0 +1,183 @@
+//! # Pause
+//!
+//! Lets a privileged origin pause whole pallets, or single calls such as the `buy` of the
+//! marketplace, while an incident is investigated.
+//!
+//! Pallets and calls are named as in the call metadata: the name a pallet is given in
+//! `construct_runtime!` and the name of the dispatchable function, and names the runtime doesn't
+//! know are rejected rather than paused to no effect. Pauses take effect once the
+//! runtime sets `frame_system::Trait::BaseCallFilter` to this module. Root dispatches are never
+//! filtered, so sudo and referenda keep working during a pause.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage,
+    dispatch::{CallMetadata, GetCallMetadata},
+    ensure,
+    traits::{Contains, EnsureOrigin, Filter, Get},
+    IterableStorageDoubleMap, IterableStorageMap,
+};
+use sp_std::prelude::*;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
+
+/// Configure the pallet by specifying the parameters and types on which it depends.
+pub trait Trait: frame_system::Trait {
+    type Event: From<Event> + Into<<Self as frame_system::Trait>::Event>;
+    /// The runtime call, whose metadata names the pallets and calls that can be paused.
+    type Call: GetCallMetadata;
+    /// Who can pause and unpause, e.g. root or the council.
+    type PauseOrigin: EnsureOrigin<Self::Origin>;
+    /// Pallets that can never be paused. This should include this pallet, the pallets used to
+    /// reach `PauseOrigin` and those with inherents, such as `Timestamp`.
+    type UnpausablePallets: Contains<Vec<u8>>;
+    /// Maximum length of a pallet or call name.
+    type MaxNameLength: Get<u32>;
+}
+
+decl_storage! {
+    trait Store for Module<T: Trait> as Pause {
+        /// Pallets none of whose calls can be dispatched.
+        pub PausedPallets get(fn pallet_paused): map hasher(blake2_128_concat) Vec<u8> => bool;
+        /// Calls that can't be dispatched, by pallet and call name.
+        pub PausedCalls get(fn call_paused): double_map hasher(blake2_128_concat) Vec<u8>, hasher(blake2_128_concat) Vec<u8> => bool;
+    }
+}
+
+decl_event!(
+    pub enum Event {
+        /// A pallet was paused. [pallet]
+        PalletPaused(Vec<u8>),
+        /// A pallet was unpaused. [pallet]
+        PalletUnpaused(Vec<u8>),
+        /// A call was paused. [pallet, call]
+        CallPaused(Vec<u8>, Vec<u8>),
+        /// A call was unpaused. [pallet, call]
+        CallUnpaused(Vec<u8>, Vec<u8>),
+    }
+);
+
+decl_error! {
+    pub enum Error for Module<T: Trait> {
+        /// The pallet is one of `UnpausablePallets`.
+        Unpausable,
+        /// The pallet or call name is longer than `MaxNameLength`.
+        NameTooLong,
+        /// No pallet with calls has this name in the runtime.
+        UnknownPallet,
+        /// The pallet has no call of this name.
+        UnknownCall,
+        AlreadyPaused,
+        NotPaused,
+    }
+}
+
+decl_module! {
+    pub struct Module<T: Trait> for enum Call where origin: T::Origin {
+        type Error = Error<T>;
+
+        fn deposit_event() = default;
+
+        const MaxNameLength: u32 = T::MaxNameLength::get();
+
+        /// Pause every call of `pallet`.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
+        pub fn pause_pallet(origin, pallet: Vec<u8>) {
+            T::PauseOrigin::ensure_origin(origin)?;
+            Self::ensure_pausable(&pallet, None)?;
+            ensure!(!Self::pallet_paused(&pallet), Error::<T>::AlreadyPaused);
+
+            PausedPallets::insert(&pallet, true);
+            Self::deposit_event(Event::PalletPaused(pallet));
+        }
+
+        /// Lift the pause of `pallet`. Calls paused one by one stay paused.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
+        pub fn unpause_pallet(origin, pallet: Vec<u8>) {
+            T::PauseOrigin::ensure_origin(origin)?;
+            ensure!(Self::pallet_paused(&pallet), Error::<T>::NotPaused);
+
+            PausedPallets::remove(&pallet);
+            Self::deposit_event(Event::PalletUnpaused(pallet));
+        }
+
+        /// Pause the call named `call` of `pallet`.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
+        pub fn pause_call(origin, pallet: Vec<u8>, call: Vec<u8>) {
+            T::PauseOrigin::ensure_origin(origin)?;
+            Self::ensure_pausable(&pallet, Some(&call[..]))?;
+            ensure!(!Self::call_paused(&pallet, &call), Error::<T>::AlreadyPaused);
+
+            PausedCalls::insert(&pallet, &call, true);
+            Self::deposit_event(Event::CallPaused(pallet, call));
+        }
+
+        /// Lift the pause of the call named `call` of `pallet`.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(1, 1)]
+        pub fn unpause_call(origin, pallet: Vec<u8>, call: Vec<u8>) {
+            T::PauseOrigin::ensure_origin(origin)?;
+            ensure!(Self::call_paused(&pallet, &call), Error::<T>::NotPaused);
+
+            PausedCalls::remove(&pallet, &call);
+            Self::deposit_event(Event::CallUnpaused(pallet, call));
+        }
+    }
+}
+
+impl<T: Trait> Module<T> {
+    /// Whether the call named `call` of `pallet` is paused, on its own or with its pallet.
+    pub fn is_paused(pallet: &[u8], call: &[u8]) -> bool {
+        PausedPallets::contains_key(pallet) || PausedCalls::contains_key(pallet, call)
+    }
+
+    /// The paused pallets.
+    pub fn paused_pallets() -> Vec<Vec<u8>> {
+        PausedPallets::iter().map(|(pallet, _)| pallet).collect()
+    }
+
+    /// The calls paused on their own, as `(pallet, call)`.
+    pub fn paused_calls() -> Vec<(Vec<u8>, Vec<u8>)> {
+        PausedCalls::iter().map(|(pallet, call, _)| (pallet, call)).collect()
+    }
+
+    fn ensure_pausable(pallet: &Vec<u8>, call: Option<&[u8]>) -> Result<(), Error<T>> {
+        let max = T::MaxNameLength::get() as usize;
+        ensure!(
+            pallet.len() <= max && call.map_or(true, |call| call.len() <= max),
+            Error::<T>::NameTooLong
+        );
+        ensure!(!T::UnpausablePallets::contains(pallet), Error::<T>::Unpausable);
+
+        let pallet_name = <T as Trait>::Call::get_module_names()
+            .iter()
+            .find(|name| name.as_bytes() == &pallet[..])
+            .ok_or(Error::<T>::UnknownPallet)?;
+        if let Some(call) = call {
+            ensure!(
+                <T as Trait>::Call::get_call_names(pallet_name)
+                    .iter()
+                    .any(|name| name.as_bytes() == call),
+                Error::<T>::UnknownCall
+            );
+        }
+        Ok(())
+    }
+}
+
+impl<T: Trait> Filter<<T as frame_system::Trait>::Call> for Module<T>
+where
+    <T as frame_system::Trait>::Call: GetCallMetadata,
+{
+    fn filter(call: &<T as frame_system::Trait>::Call) -> bool {
+        let CallMetadata {
+            pallet_name,
+            function_name,
+        } = call.get_call_metadata();
+        !Self::is_paused(pallet_name.as_bytes(), function_name.as_bytes())
+    }
+}
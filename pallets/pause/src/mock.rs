@@ -0,0 +1,132 @@
+use crate::{Module, Trait};
+use frame_support::{impl_outer_dispatch, impl_outer_event, impl_outer_origin, parameter_types, weights::Weight};
+use frame_system as system;
+use sp_core::H256;
+use sp_runtime::{
+    testing::Header,
+    traits::{BlakeTwo256, IdentityLookup},
+    Perbill,
+};
+
+impl_outer_origin! {
+    pub enum Origin for Test {}
+}
+
+mod pause {
+    pub use crate::Event;
+}
+
+impl_outer_event! {
+    pub enum Event for Test {
+        frame_system<T>,
+        pallet_balances<T>,
+        pause,
+    }
+}
+
+impl_outer_dispatch! {
+    pub enum Call for Test where origin: Origin {
+        frame_system::System,
+        pallet_balances::Balances,
+    }
+}
+
+// Configure a mock runtime to test the pallet.
+
+#[derive(Clone, Eq, PartialEq)]
+pub struct Test;
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+    pub const MaximumBlockWeight: Weight = 1024;
+    pub const MaximumBlockLength: u32 = 2 * 1024;
+    pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+}
+
+impl system::Trait for Test {
+    type BaseCallFilter = Pause;
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = u64;
+    type Hash = H256;
+    type Hashing = BlakeTwo256;
+    type AccountId = u64;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type MaximumBlockWeight = MaximumBlockWeight;
+    type DbWeight = ();
+    type BlockExecutionWeight = ();
+    type ExtrinsicBaseWeight = ();
+    type MaximumExtrinsicWeight = MaximumBlockWeight;
+    type MaximumBlockLength = MaximumBlockLength;
+    type AvailableBlockRatio = AvailableBlockRatio;
+    type Version = ();
+    type PalletInfo = ();
+    type AccountData = pallet_balances::AccountData<u64>;
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+}
+
+parameter_types! {
+    pub const ExistentialDeposit: u64 = 1;
+}
+impl pallet_balances::Trait for Test {
+    type MaxLocks = ();
+    type Balance = u64;
+    type Event = Event;
+    type DustRemoval = ();
+    type ExistentialDeposit = ExistentialDeposit;
+    type AccountStore = System;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const MaxNameLength: u32 = 16;
+}
+
+pub struct UnpausablePallets;
+impl frame_support::traits::Contains<Vec<u8>> for UnpausablePallets {
+    fn sorted_members() -> Vec<Vec<u8>> {
+        vec![b"Pause".to_vec(), b"System".to_vec()]
+    }
+}
+
+impl Trait for Test {
+    type Event = Event;
+    type Call = Call;
+    type PauseOrigin = system::EnsureRoot<u64>;
+    type UnpausablePallets = UnpausablePallets;
+    type MaxNameLength = MaxNameLength;
+}
+
+pub type System = frame_system::Module<Test>;
+pub type Balances = pallet_balances::Module<Test>;
+
+pub type Pause = Module<Test>;
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+    let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+
+    pallet_balances::GenesisConfig::<Test> {
+        balances: vec![(100, 500), (200, 500)],
+    }.assimilate_storage(&mut t).unwrap();
+
+    let mut t: sp_io::TestExternalities = t.into();
+
+    t.execute_with(|| System::set_block_number(1));
+    t
+}
+
+/// The `pause` events deposited so far, oldest first.
+pub fn pause_events() -> Vec<crate::Event> {
+    System::events()
+        .into_iter()
+        .filter_map(|record| match record.event {
+            Event::pause(event) => Some(event),
+            _ => None,
+        })
+        .collect()
+}
@@ -0,0 +1,111 @@
+use crate::{mock::*, Error, Event as PauseEvent};
+use frame_support::{
+    assert_noop, assert_ok,
+    dispatch::Dispatchable,
+    traits::Filter,
+};
+use pallet_balances::Call as BalancesCall;
+use sp_runtime::DispatchError;
+
+fn transfer() -> Call {
+    Call::Balances(BalancesCall::transfer(200, 10))
+}
+
+fn transfer_keep_alive() -> Call {
+    Call::Balances(BalancesCall::transfer_keep_alive(200, 10))
+}
+
+#[test]
+fn pause_pallet() {
+    new_test_ext().execute_with(|| {
+        assert!(<Pause as Filter<Call>>::filter(&transfer()));
+
+        assert_ok!(Pause::pause_pallet(Origin::root(), b"Balances".to_vec()));
+        assert!(!<Pause as Filter<Call>>::filter(&transfer()));
+        assert!(!<Pause as Filter<Call>>::filter(&transfer_keep_alive()));
+        assert_eq!(Pause::paused_pallets(), vec![b"Balances".to_vec()]);
+
+        // signed dispatches go through the filter, root ones don't
+        assert_noop!(
+            transfer().dispatch(Origin::signed(100)),
+            DispatchError::BadOrigin,
+        );
+        assert_ok!(Call::Balances(BalancesCall::set_balance(200, 100, 0)).dispatch(Origin::root()));
+
+        assert_ok!(Pause::unpause_pallet(Origin::root(), b"Balances".to_vec()));
+        assert!(<Pause as Filter<Call>>::filter(&transfer()));
+        assert_ok!(transfer().dispatch(Origin::signed(100)));
+
+        assert_eq!(pause_events(), vec![
+            PauseEvent::PalletPaused(b"Balances".to_vec()),
+            PauseEvent::PalletUnpaused(b"Balances".to_vec()),
+        ]);
+    });
+}
+
+#[test]
+fn pause_call() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(Pause::pause_call(Origin::root(), b"Balances".to_vec(), b"transfer".to_vec()));
+        assert!(!<Pause as Filter<Call>>::filter(&transfer()));
+        assert!(<Pause as Filter<Call>>::filter(&transfer_keep_alive()));
+        assert_eq!(Pause::paused_calls(), vec![(b"Balances".to_vec(), b"transfer".to_vec())]);
+
+        // unpausing the pallet leaves the paused call alone
+        assert_ok!(Pause::pause_pallet(Origin::root(), b"Balances".to_vec()));
+        assert_ok!(Pause::unpause_pallet(Origin::root(), b"Balances".to_vec()));
+        assert!(!<Pause as Filter<Call>>::filter(&transfer()));
+
+        assert_ok!(Pause::unpause_call(Origin::root(), b"Balances".to_vec(), b"transfer".to_vec()));
+        assert!(<Pause as Filter<Call>>::filter(&transfer()));
+        assert!(Pause::paused_calls().is_empty());
+
+        assert_eq!(pause_events().first(), Some(&PauseEvent::CallPaused(b"Balances".to_vec(), b"transfer".to_vec())));
+        assert_eq!(pause_events().last(), Some(&PauseEvent::CallUnpaused(b"Balances".to_vec(), b"transfer".to_vec())));
+    });
+}
+
+#[test]
+fn pause_errors() {
+    new_test_ext().execute_with(|| {
+        assert_noop!(Pause::pause_pallet(Origin::signed(100), b"Balances".to_vec()), DispatchError::BadOrigin);
+        assert_noop!(
+            Pause::pause_call(Origin::signed(100), b"Balances".to_vec(), b"transfer".to_vec()),
+            DispatchError::BadOrigin,
+        );
+
+        assert_noop!(Pause::pause_pallet(Origin::root(), b"Pause".to_vec()), Error::<Test>::Unpausable);
+        assert_noop!(
+            Pause::pause_call(Origin::root(), b"System".to_vec(), b"remark".to_vec()),
+            Error::<Test>::Unpausable,
+        );
+        assert_noop!(Pause::pause_pallet(Origin::root(), vec![b'a'; 17]), Error::<Test>::NameTooLong);
+        assert_noop!(Pause::pause_pallet(Origin::root(), b"balances".to_vec()), Error::<Test>::UnknownPallet);
+        assert_noop!(
+            Pause::pause_call(Origin::root(), b"Balance".to_vec(), b"transfer".to_vec()),
+            Error::<Test>::UnknownPallet,
+        );
+        assert_noop!(
+            Pause::pause_call(Origin::root(), b"Balances".to_vec(), b"Transfer".to_vec()),
+            Error::<Test>::UnknownCall,
+        );
+        assert_noop!(
+            Pause::pause_call(Origin::root(), b"Balances".to_vec(), vec![b'a'; 17]),
+            Error::<Test>::NameTooLong,
+        );
+
+        assert_noop!(Pause::unpause_pallet(Origin::root(), b"Balances".to_vec()), Error::<Test>::NotPaused);
+        assert_noop!(
+            Pause::unpause_call(Origin::root(), b"Balances".to_vec(), b"transfer".to_vec()),
+            Error::<Test>::NotPaused,
+        );
+
+        assert_ok!(Pause::pause_pallet(Origin::root(), b"Balances".to_vec()));
+        assert_noop!(Pause::pause_pallet(Origin::root(), b"Balances".to_vec()), Error::<Test>::AlreadyPaused);
+        assert_ok!(Pause::pause_call(Origin::root(), b"Balances".to_vec(), b"transfer".to_vec()));
+        assert_noop!(
+            Pause::pause_call(Origin::root(), b"Balances".to_vec(), b"transfer".to_vec()),
+            Error::<Test>::AlreadyPaused,
+        );
+    });
+}
@@ -0,0 +1,47 @@
+//! Runtime API definition for the royalty-nft pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// The `too_many_arguments` warning originates from `decl_runtime_apis` macro.
+#![allow(clippy::too_many_arguments)]
+// The `unnecessary_mut_passed` warning originates from `decl_runtime_apis` macro.
+#![allow(clippy::unnecessary_mut_passed)]
+
+use codec::Codec;
+use sp_runtime::RuntimeString;
+use sp_std::prelude::*;
+
+pub use royalty_nft::{ClassDetails, Listing, ListingFilter, MintQuote, SaleQuote};
+
+sp_api::decl_runtime_apis! {
+    pub trait RoyaltyApi<AccountId, ClassId, TokenId, Balance> where
+        AccountId: Codec,
+        ClassId: Codec,
+        TokenId: Codec,
+        Balance: Codec,
+    {
+        /// Marketplace details of a class, or `None` if it does not exist.
+        fn class_info(class_id: ClassId) -> Option<ClassDetails<AccountId, Balance>>;
+
+        /// Page through the tokens currently for sale that match `filter`.
+        fn listings(
+            filter: ListingFilter<AccountId, ClassId, Balance>,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<Listing<AccountId, ClassId, TokenId, Balance>>;
+
+        /// Quote a `buy` of a listed token. If `buyer` is given, the buyer's balance is checked
+        /// as well and the error `buy` would fail with is returned.
+        fn quote_sale(
+            class_id: ClassId,
+            token_id: TokenId,
+            buyer: Option<AccountId>,
+        ) -> Result<SaleQuote<AccountId, Balance>, RuntimeString>;
+
+        /// Quote a `mint_nft_token` in a class. If `minter` is given, the minter's balance is
+        /// checked as well and the error minting would fail with is returned.
+        fn quote_mint(
+            class_id: ClassId,
+            minter: Option<AccountId>,
+        ) -> Result<MintQuote<AccountId, Balance>, RuntimeString>;
+    }
+}
@@ -0,0 +1,197 @@
+//! RPC interface for the royalty-nft pallet.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{de::DeserializeOwned, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT, RuntimeString};
+
+pub use royalty_nft_rpc_runtime_api::{
+    ClassDetails, Listing, ListingFilter, MintQuote, RoyaltyApi as RoyaltyRuntimeApi, SaleQuote,
+};
+
+/// Number of listings returned when the caller does not ask for a page size.
+const DEFAULT_LISTINGS_LIMIT: u32 = 50;
+/// Upper bound on the page size, so a single call can't walk the whole `Sales` map.
+const MAX_LISTINGS_LIMIT: u32 = 500;
+
+#[rpc]
+pub trait RoyaltyNftApi<BlockHash, AccountId, ClassId, TokenId, Balance> {
+    /// Marketplace details of a class: owner, minting info, royalty and whether it is
+    /// soulbound. Returns `null` for unknown classes.
+    #[rpc(name = "loot_classInfo")]
+    fn class_info(
+        &self,
+        class_id: ClassId,
+        at: Option<BlockHash>,
+    ) -> Result<Option<ClassDetails<AccountId, Balance>>>;
+
+    /// Page through the tokens currently for sale, optionally filtered by class, seller and
+    /// price range. Pass the same `at` for every page to get a consistent view.
+    #[rpc(name = "loot_listings")]
+    fn listings(
+        &self,
+        filter: Option<ListingFilter<AccountId, ClassId, Balance>>,
+        offset: Option<u32>,
+        limit: Option<u32>,
+        at: Option<BlockHash>,
+    ) -> Result<Vec<Listing<AccountId, ClassId, TokenId, Balance>>>;
+
+    /// Quote what buying a listed token costs and how the price is split between the seller,
+    /// the class owner and the platform. With `buyer` set, fails with the error `buy` would.
+    #[rpc(name = "loot_quoteSale")]
+    fn quote_sale(
+        &self,
+        class_id: ClassId,
+        token_id: TokenId,
+        buyer: Option<AccountId>,
+        at: Option<BlockHash>,
+    ) -> Result<SaleQuote<AccountId, Balance>>;
+
+    /// Quote what minting a token in a class costs. With `minter` set, fails with the error
+    /// `mint_nft_token` would.
+    #[rpc(name = "loot_quoteMint")]
+    fn quote_mint(
+        &self,
+        class_id: ClassId,
+        minter: Option<AccountId>,
+        at: Option<BlockHash>,
+    ) -> Result<MintQuote<AccountId, Balance>>;
+}
+
+/// A struct that implements the [`RoyaltyNftApi`].
+pub struct RoyaltyNft<C, B> {
+    client: Arc<C>,
+    _marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> RoyaltyNft<C, B> {
+    /// Create new `RoyaltyNft` with the given reference to the client.
+    pub fn new(client: Arc<C>) -> Self {
+        RoyaltyNft {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+/// Error type of this RPC api.
+pub enum Error {
+    /// The call to runtime failed.
+    RuntimeError,
+    /// The runtime rejected the quoted action.
+    QuoteRejected,
+}
+
+impl From<Error> for i64 {
+    fn from(e: Error) -> i64 {
+        match e {
+            Error::RuntimeError => 1,
+            Error::QuoteRejected => 2,
+        }
+    }
+}
+
+fn runtime_error(message: &str, e: impl std::fmt::Debug) -> RpcError {
+    RpcError {
+        code: ErrorCode::ServerError(Error::RuntimeError.into()),
+        message: message.into(),
+        data: Some(format!("{:?}", e).into()),
+    }
+}
+
+fn quote_rejected(e: RuntimeString) -> RpcError {
+    let message = match e {
+        RuntimeString::Borrowed(message) => message.into(),
+        RuntimeString::Owned(message) => message,
+    };
+
+    RpcError {
+        code: ErrorCode::ServerError(Error::QuoteRejected.into()),
+        message,
+        data: None,
+    }
+}
+
+impl<C, Block, AccountId, ClassId, TokenId, Balance>
+    RoyaltyNftApi<<Block as BlockT>::Hash, AccountId, ClassId, TokenId, Balance>
+    for RoyaltyNft<C, Block>
+where
+    Block: BlockT,
+    C: Send + Sync + 'static,
+    C: ProvideRuntimeApi<Block>,
+    C: HeaderBackend<Block>,
+    C::Api: RoyaltyRuntimeApi<Block, AccountId, ClassId, TokenId, Balance>,
+    AccountId: Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+    ClassId: Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+    TokenId: Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+    Balance: Codec + Serialize + DeserializeOwned + Send + Sync + 'static,
+{
+    fn class_info(
+        &self,
+        class_id: ClassId,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Option<ClassDetails<AccountId, Balance>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.class_info(&at, class_id)
+            .map_err(|e| runtime_error("Unable to query class info.", e))
+    }
+
+    fn listings(
+        &self,
+        filter: Option<ListingFilter<AccountId, ClassId, Balance>>,
+        offset: Option<u32>,
+        limit: Option<u32>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<Vec<Listing<AccountId, ClassId, TokenId, Balance>>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+        let filter = filter.unwrap_or(ListingFilter {
+            class_id: None,
+            seller: None,
+            min_price: None,
+            max_price: None,
+        });
+        let limit = limit
+            .unwrap_or(DEFAULT_LISTINGS_LIMIT)
+            .min(MAX_LISTINGS_LIMIT);
+
+        api.listings(&at, filter, offset.unwrap_or_default(), limit)
+            .map_err(|e| runtime_error("Unable to query listings.", e))
+    }
+
+    fn quote_sale(
+        &self,
+        class_id: ClassId,
+        token_id: TokenId,
+        buyer: Option<AccountId>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<SaleQuote<AccountId, Balance>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.quote_sale(&at, class_id, token_id, buyer)
+            .map_err(|e| runtime_error("Unable to quote sale.", e))?
+            .map_err(quote_rejected)
+    }
+
+    fn quote_mint(
+        &self,
+        class_id: ClassId,
+        minter: Option<AccountId>,
+        at: Option<<Block as BlockT>::Hash>,
+    ) -> Result<MintQuote<AccountId, Balance>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        api.quote_mint(&at, class_id, minter)
+            .map_err(|e| runtime_error("Unable to quote mint.", e))?
+            .map_err(quote_rejected)
+    }
+}
@@ -1,15 +1,30 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use codec::{Decode, Encode};
 use frame_support::traits::Currency;
+use frame_support::traits::EnsureOrigin;
 use frame_support::traits::ExistenceRequirement;
+use frame_support::traits::{OnUnbalanced, WithdrawReason};
 /// Edit this file to define custom logic or remove it if it is not needed.
 /// Learn more about FRAME and the core library of Substrate FRAME pallets:
 /// https://substrate.dev/docs/en/knowledgebase/runtime/frame
-use frame_support::{decl_error, decl_event, decl_module, decl_storage, ensure, traits::Get};
+use frame_support::{
+    decl_error, decl_event, decl_module, decl_storage, ensure, traits::Get, transactional,
+    weights::Weight, IterableStorageDoubleMap,
+};
 use frame_system::ensure_signed;
 
-use sp_runtime::{traits::StaticLookup, DispatchError, DispatchResult};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::{
+    helpers_128bit::multiply_by_rational,
+    traits::{AccountIdConversion, SaturatedConversion, Saturating, StaticLookup, Zero},
+    DispatchError, DispatchResult, ModuleId, RuntimeDebug,
+};
 use sp_arithmetic::Permill;
+use sp_std::prelude::*;
+
+use orml_nft::{InspectNonFungibles, NonFungibles, OnNftBurn, OnNftTransfer};
 
 #[cfg(test)]
 mod mock;
@@ -19,12 +34,208 @@ mod tests;
 
 type BalanceOf<T> =
     <<T as Trait>::Currency as Currency<<T as frame_system::Trait>::AccountId>>::Balance;
+type NegativeImbalanceOf<T> = <<T as Trait>::Currency as Currency<
+    <T as frame_system::Trait>::AccountId,
+>>::NegativeImbalance;
+type NftOf<T> = <T as Trait>::Nft;
+pub type ClassIdOf<T> =
+    <NftOf<T> as InspectNonFungibles<<T as frame_system::Trait>::AccountId>>::ClassId;
+pub type TokenIdOf<T> =
+    <NftOf<T> as InspectNonFungibles<<T as frame_system::Trait>::AccountId>>::TokenId;
+pub type ClassDataOf<T> =
+    <NftOf<T> as InspectNonFungibles<<T as frame_system::Trait>::AccountId>>::ClassData;
+pub type TokenDataOf<T> =
+    <NftOf<T> as InspectNonFungibles<<T as frame_system::Trait>::AccountId>>::TokenData;
+
+/// A token that is currently for sale, together with the royalty the class owner will receive.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct Listing<AccountId, ClassId, TokenId, Balance> {
+    pub class_id: ClassId,
+    pub token_id: TokenId,
+    pub seller: AccountId,
+    pub price: Balance,
+    pub royalty: Balance,
+}
+
+/// Optional constraints used when paging through the listings. Unset fields match everything.
+#[derive(Encode, Decode, Clone, Default, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase", default))]
+pub struct ListingFilter<AccountId, ClassId, Balance> {
+    pub class_id: Option<ClassId>,
+    pub seller: Option<AccountId>,
+    pub min_price: Option<Balance>,
+    pub max_price: Option<Balance>,
+}
+
+impl<AccountId: PartialEq, ClassId, Balance: PartialOrd> ListingFilter<AccountId, ClassId, Balance> {
+    fn matches<TokenId>(&self, listing: &Listing<AccountId, ClassId, TokenId, Balance>) -> bool {
+        self.seller.as_ref().map_or(true, |seller| *seller == listing.seller)
+            && self.min_price.as_ref().map_or(true, |min| listing.price >= *min)
+            && self.max_price.as_ref().map_or(true, |max| listing.price <= *max)
+    }
+}
+
+/// What a `buy` of a listed token would cost and how the price is split.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct SaleQuote<AccountId, Balance> {
+    /// Current owner of the token, who receives `seller_receives`.
+    pub seller: AccountId,
+    /// Class owner, who receives `royalty`.
+    pub royalty_recipient: AccountId,
+    /// Total amount paid by the buyer.
+    pub price: Balance,
+    pub royalty: Balance,
+    /// Cut of the price the marketplace keeps, see `Trait::PlatformFee`.
+    pub platform_fee: Balance,
+    pub seller_receives: Balance,
+}
+
+/// What a `mint_nft_token` in a class would cost.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct MintQuote<AccountId, Balance> {
+    /// Class owner, who receives the minting price.
+    pub class_owner: AccountId,
+    pub price: Balance,
+}
+
+/// Everything the marketplace knows about a class, as returned by the `class_info` runtime API.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ClassDetails<AccountId, Balance> {
+    pub owner: AccountId,
+    pub can_mint: bool,
+    pub mint_price: Balance,
+    pub royalty: u32,
+    /// Tokens of a soulbound class can't be transferred or sold, only burned.
+    pub soulbound: bool,
+}
+
+/// A token locked in the vault and split into fungible shares.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct Fraction<AccountId, Balance> {
+    /// Who fractionalized the token.
+    pub issuer: AccountId,
+    pub total_shares: u64,
+    /// Number of accounts holding shares.
+    pub holders: u32,
+    /// What anyone can pay to take the token out of the vault, split between the share holders.
+    pub buyout_price: Balance,
+}
+
+/// A token minted at genesis, optionally listed for sale.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct GenesisToken<AccountId, TokenData, Balance> {
+    pub owner: AccountId,
+    pub metadata: Vec<u8>,
+    pub data: TokenData,
+    /// Listing price, if the token is for sale.
+    pub sale: Option<Balance>,
+}
+
+/// A class created at genesis, as `create_nft_class` would, with its tokens.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct GenesisClass<AccountId, ClassData, TokenData, Balance> {
+    pub owner: AccountId,
+    pub metadata: Vec<u8>,
+    pub data: ClassData,
+    pub mint_price: Balance,
+    pub royalty: u32,
+    pub soulbound: bool,
+    pub tokens: Vec<GenesisToken<AccountId, TokenData, Balance>>,
+}
+
+/// Who froze a class or token. A freeze can only be lifted by the same or a stronger freezer.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum FrozenBy {
+    /// The class owner.
+    ClassOwner,
+    /// `Trait::FreezeOrigin`, e.g. support investigating a dispute.
+    FreezeOrigin,
+}
+
+pub type ClassDetailsOf<T> = ClassDetails<<T as frame_system::Trait>::AccountId, BalanceOf<T>>;
+pub type SaleQuoteOf<T> = SaleQuote<<T as frame_system::Trait>::AccountId, BalanceOf<T>>;
+pub type MintQuoteOf<T> = MintQuote<<T as frame_system::Trait>::AccountId, BalanceOf<T>>;
+pub type FractionOf<T> = Fraction<<T as frame_system::Trait>::AccountId, BalanceOf<T>>;
+pub type GenesisClassOf<T> = GenesisClass<
+    <T as frame_system::Trait>::AccountId,
+    ClassDataOf<T>,
+    TokenDataOf<T>,
+    BalanceOf<T>,
+>;
+
+pub type ListingOf<T> = Listing<
+    <T as frame_system::Trait>::AccountId,
+    ClassIdOf<T>,
+    TokenIdOf<T>,
+    BalanceOf<T>,
+>;
+pub type ListingFilterOf<T> = ListingFilter<
+    <T as frame_system::Trait>::AccountId,
+    ClassIdOf<T>,
+    BalanceOf<T>,
+>;
+
+/// Lets the runtime fill in the parts of class and token data the marketplace vouches for, such
+/// as a royalty snapshot, rather than trusting what the caller supplied.
+pub trait DataPolicy<AccountId, ClassData, TokenData> {
+    /// The data to store for a new class created by `owner`.
+    fn class_data(owner: &AccountId, data: ClassData) -> ClassData;
+    /// The data to store for a new token of a class whose royalty is currently `royalty`.
+    fn token_data(royalty: u32, data: TokenData) -> TokenData;
+}
+
+impl<AccountId, ClassData, TokenData> DataPolicy<AccountId, ClassData, TokenData> for () {
+    fn class_data(_owner: &AccountId, data: ClassData) -> ClassData {
+        data
+    }
+
+    fn token_data(_royalty: u32, data: TokenData) -> TokenData {
+        data
+    }
+}
 
 /// Configure the pallet by specifying the parameters and types on which it depends.
-pub trait Trait: frame_system::Trait + orml_nft::Trait {
+pub trait Trait: frame_system::Trait {
     /// Because this pallet emits events, it depends on the runtime's definition of an event.
     type Event: From<Event<Self>> + Into<<Self as frame_system::Trait>::Event>;
     type Currency: Currency<Self::AccountId>;
+    /// Where classes and tokens are stored, e.g. `orml_nft::Module`.
+    ///
+    /// Listings are cleared through this module's `OnNftTransfer` and `OnNftBurn` hooks, so the
+    /// implementation must call them (for `orml_nft`, set `OnTransfer` and `OnBurn` to this module).
+    type Nft: NonFungibles<Self::AccountId>;
+    /// Maximum number of tokens in a single `batch_mint` or `batch_transfer`.
+    type MaxBatchSize: Get<u32>;
+    /// Who besides the class owner can freeze and thaw tokens and classes, e.g. while a
+    /// dispute over a stolen item is investigated.
+    type FreezeOrigin: EnsureOrigin<Self::Origin>;
+    /// Used to derive the vault account holding fractionalized tokens.
+    type ModuleId: Get<ModuleId>;
+    /// Maximum number of accounts holding shares of a fractionalized token, which bounds the
+    /// payouts of a buyout.
+    type MaxShareHolders: Get<u32>;
+    /// Adjusts the data of new classes and tokens before they are stored.
+    type DataPolicy: DataPolicy<Self::AccountId, ClassDataOf<Self>, TokenDataOf<Self>>;
+    /// Share of the price of every sale and buyout the marketplace keeps.
+    type PlatformFee: Get<Permill>;
+    /// Handler for platform fees, e.g. the treasury. They are burned if this is `()`.
+    type OnPlatformFee: OnUnbalanced<NegativeImbalanceOf<Self>>;
 }
 
 // The pallet's runtime storage items.
@@ -34,8 +245,49 @@ decl_storage! {
     // This name may be updated, but each pallet in the runtime must use a unique name.
     // ---------------------------------vvvvvvvvvvvvvv
     trait Store for Module<T: Trait> as TemplateModule {
-        pub Info get(fn info): map hasher(blake2_128_concat) T::ClassId => Option<(bool, BalanceOf<T>, u32)>;
-        pub Sales get(fn sales): double_map hasher(twox_64_concat) T::ClassId, hasher(twox_64_concat) T::TokenId => Option<BalanceOf<T>>;
+        pub Info get(fn info): map hasher(blake2_128_concat) ClassIdOf<T> => Option<(bool, BalanceOf<T>, u32)>;
+        pub Sales get(fn sales): double_map hasher(twox_64_concat) ClassIdOf<T>, hasher(twox_64_concat) TokenIdOf<T> => Option<BalanceOf<T>>;
+        /// Classes whose tokens can never change owner.
+        pub Soulbound get(fn soulbound): map hasher(twox_64_concat) ClassIdOf<T> => bool;
+        /// Classes whose tokens can't be transferred, listed, sold or burned until thawed, by
+        /// who froze them.
+        pub FrozenClasses get(fn frozen_class): map hasher(twox_64_concat) ClassIdOf<T> => Option<FrozenBy>;
+        /// Tokens that can't be transferred, listed, sold or burned until thawed, by who froze
+        /// them.
+        pub FrozenTokens get(fn frozen_token): double_map hasher(twox_64_concat) ClassIdOf<T>, hasher(twox_64_concat) TokenIdOf<T> => Option<FrozenBy>;
+        /// Tokens locked in the vault and split into shares.
+        pub Fractions get(fn fractions): double_map hasher(twox_64_concat) ClassIdOf<T>, hasher(twox_64_concat) TokenIdOf<T> => Option<FractionOf<T>>;
+        /// Shares of each fractionalized token, by holder.
+        pub Shares get(fn shares): double_map hasher(twox_64_concat) (ClassIdOf<T>, TokenIdOf<T>), hasher(blake2_128_concat) T::AccountId => u64;
+    }
+    add_extra_genesis {
+        /// Classes to create, with their tokens, e.g. demo collections of a test network.
+        config(classes): Vec<GenesisClassOf<T>>;
+
+        build(|config: &GenesisConfig<T>| {
+            for class in &config.classes {
+                assert!(class.royalty < 100, "Genesis class royalty must be below 100");
+                let data = T::DataPolicy::class_data(&class.owner, class.data.clone());
+                let class_id = T::Nft::create_class(&class.owner, class.metadata.clone(), data)
+                    .expect("Create class cannot fail while building genesis");
+
+                Info::<T>::insert(class_id, (true, class.mint_price, class.royalty));
+                if class.soulbound {
+                    Soulbound::<T>::insert(class_id, true);
+                }
+
+                for token in &class.tokens {
+                    let data = T::DataPolicy::token_data(class.royalty, token.data.clone());
+                    let token_id = T::Nft::mint(&token.owner, class_id, token.metadata.clone(), data)
+                        .expect("Token mint cannot fail during genesis");
+
+                    if let Some(price) = token.sale {
+                        assert!(!class.soulbound, "Genesis tokens of soulbound classes can't be for sale");
+                        Sales::<T>::insert(class_id, token_id, price);
+                    }
+                }
+            }
+        })
     }
 }
 
@@ -45,19 +297,53 @@ decl_event!(
     pub enum Event<T>
     where
         AccountId = <T as frame_system::Trait>::AccountId,
-        ClassId = <T as orml_nft::Trait>::ClassId,
+        ClassId = ClassIdOf<T>,
         Balance = BalanceOf<T>,
-        TokenId = <T as orml_nft::Trait>::TokenId,
+        TokenId = TokenIdOf<T>,
     {
-        /// Event documentation should end with an array that provides descriptive names for event
-        /// parameters. [something, who]
-        OrmlNftClassCreated(AccountId, ClassId),
-        OrmlNftTokenMinted(AccountId, TokenId),
-        OrmlNftTokenTransferred(AccountId, AccountId, ClassId, TokenId),
-        RoyaltySent(AccountId, Balance),
-        TokenSaleCreated(ClassId, TokenId),
-        TokenSaleDeleted(ClassId, TokenId),
-        TokenSaleCompleted(AccountId, ClassId, TokenId),
+        /// A class was created. [owner, class_id, mint_price, royalty, soulbound]
+        ClassCreated(AccountId, ClassId, Balance, u32, bool),
+        /// The minting info of a class changed. [class_id, can_mint, mint_price, royalty]
+        ClassInfoUpdated(ClassId, bool, Balance, u32),
+        /// A token was minted and the mint price paid to the class owner. The price is zero for
+        /// tokens minted by the class owner through `batch_mint`.
+        /// [owner, class_id, token_id, mint_price]
+        TokenMinted(AccountId, ClassId, TokenId, Balance),
+        /// A token was transferred; any listing of it is removed. [from, to, class_id, token_id]
+        TokenTransferred(AccountId, AccountId, ClassId, TokenId),
+        /// A royalty was paid to the class owner. [payer, recipient, class_id, royalty]
+        RoyaltySent(AccountId, AccountId, ClassId, Balance),
+        /// The platform fee of a sale or buyout was paid. [payer, class_id, fee]
+        PlatformFeePaid(AccountId, ClassId, Balance),
+        /// A token was listed, or its listing price changed. [seller, class_id, token_id, price]
+        TokenSaleCreated(AccountId, ClassId, TokenId, Balance),
+        /// A listing was withdrawn. [seller, class_id, token_id]
+        TokenSaleDeleted(AccountId, ClassId, TokenId),
+        /// A listed token was bought. [seller, buyer, class_id, token_id, price, royalty]
+        TokenSaleCompleted(AccountId, AccountId, ClassId, TokenId, Balance, Balance),
+        /// A token was burned; any listing of it is removed. [owner, class_id, token_id]
+        TokenBurned(AccountId, ClassId, TokenId),
+        /// A token was nested in another token.
+        /// [owner, class_id, token_id, parent_class_id, parent_token_id]
+        TokenNested(AccountId, ClassId, TokenId, ClassId, TokenId),
+        /// A token was locked in the vault and its shares issued to the owner.
+        /// [owner, class_id, token_id, shares, buyout_price]
+        TokenFractionalized(AccountId, ClassId, TokenId, u64, Balance),
+        /// Shares of a fractionalized token were transferred. [from, to, class_id, token_id, shares]
+        SharesTransferred(AccountId, AccountId, ClassId, TokenId, u64),
+        /// All shares of a token were redeemed for the token. [owner, class_id, token_id]
+        TokenRedeemed(AccountId, ClassId, TokenId),
+        /// A fractionalized token was bought out of the vault and the share holders paid.
+        /// [buyer, class_id, token_id, price, royalty]
+        TokenBoughtOut(AccountId, ClassId, TokenId, Balance, Balance),
+        /// A class was frozen. [class_id]
+        ClassFrozen(ClassId),
+        /// A class was thawed. [class_id]
+        ClassThawed(ClassId),
+        /// A token was frozen. [class_id, token_id]
+        TokenFrozen(ClassId, TokenId),
+        /// A token was thawed. [class_id, token_id]
+        TokenThawed(ClassId, TokenId),
     }
 );
 
@@ -74,11 +360,24 @@ decl_error! {
         InvalidPermission,
         TokenNotFound,
         TokenNotOwned,
+        TokenIsSoulbound,
+        ClassIsFrozen,
+        TokenIsFrozen,
+        /// The class or token is already frozen, by the caller or by a stronger freezer.
+        AlreadyFrozen,
+        NotFrozen,
+        TokenNotFractionalized,
+        InvalidShares,
+        NotEnoughShares,
+        TooManyShareHolders,
         TokenNotForSale,
         BuyerSellerSame,
         NotEnoughFunds,
         BalanceLessThanMininum,
         InvalidRoyalty,
+        BatchTooLarge,
+        /// A share holder's cut of a buyout can't be computed.
+        PayoutOverflow,
     }
 }
 
@@ -93,38 +392,45 @@ decl_module! {
         // Events must be initialized if they are used by the pallet.
         fn deposit_event() = default;
 
+        const MaxBatchSize: u32 = T::MaxBatchSize::get();
+        const ModuleId: ModuleId = T::ModuleId::get();
+        const MaxShareHolders: u32 = T::MaxShareHolders::get();
+        const PlatformFee: Permill = T::PlatformFee::get();
+
         //
         // "CID": "Vec<u8>"
         // https://github.com/open-web3-stack/open-runtime-module-library/blob/f278c766d8bcc36b94c0e0c63d1205a4e5351841/nft/src/lib.rs#L34
         //
-        // "ClassData": "u32"
-        // "TokenData": "u32"
-        // https://github.com/open-web3-stack/open-runtime-module-library/blob/f278c766d8bcc36b94c0e0c63d1205a4e5351841/nft/src/lib.rs#L66
+        // "ClassData" and "TokenData" are structs in the Loot runtime, see `type.json`
         //
         // "ClassId": "u64"
         // "TokenId": "u64"
         // https://github.com/open-web3-stack/open-runtime-module-library/blob/f278c766d8bcc36b94c0e0c63d1205a4e5351841/nft/src/lib.rs#L62
         #[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-        pub fn create_nft_class(origin, class_metadata: orml_nft::CID, class_data : <T as orml_nft::Trait>::ClassData, price: BalanceOf<T>, royalty: u32) -> DispatchResult {
+        pub fn create_nft_class(origin, class_metadata: orml_nft::CID, class_data : ClassDataOf<T>, price: BalanceOf<T>, royalty: u32, soulbound: bool) -> DispatchResult {
             let who = ensure_signed(origin)?;
 
-            let class_id = orml_nft::Module::<T>::next_class_id();
-            let token_id = orml_nft::Module::<T>::create_class(&who, class_metadata, class_data)?;
+            let class_data = T::DataPolicy::class_data(&who, class_data);
+            let class_id = T::Nft::create_class(&who, class_metadata, class_data)?;
 
             Info::<T>::insert(class_id, (true, price, royalty));
+            if soulbound {
+                Soulbound::<T>::insert(class_id, true);
+            }
 
-            Self::deposit_event(RawEvent::OrmlNftClassCreated(who, token_id));
+            Self::deposit_event(RawEvent::ClassCreated(who, class_id, price, royalty, soulbound));
 
             Ok(())
         }
 
         #[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-        pub fn set_mintable(origin, class_id: T::ClassId, can_mint: bool) -> DispatchResult {
+        pub fn set_mintable(origin, class_id: ClassIdOf<T>, can_mint: bool) -> DispatchResult {
             Self::ensure_class_owner(origin, class_id)?;
 
             Info::<T>::try_mutate(class_id, |info| -> DispatchResult {
                 let (_, price, royalty) = info.ok_or(Error::<T>::InvalidClassId)?;
                 *info = Some((can_mint, price, royalty));
+                Self::deposit_event(RawEvent::ClassInfoUpdated(class_id, can_mint, price, royalty));
 
                 Ok(())
             })?;
@@ -133,12 +439,13 @@ decl_module! {
         }
 
         #[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-        pub fn set_price(origin, class_id: T::ClassId, price: BalanceOf<T>) -> DispatchResult {
+        pub fn set_price(origin, class_id: ClassIdOf<T>, price: BalanceOf<T>) -> DispatchResult {
             Self::ensure_class_owner(origin, class_id)?;
 
             Info::<T>::try_mutate(class_id, |info| -> DispatchResult {
                 let (can_mint, _, royalty) = info.ok_or(Error::<T>::InvalidClassId)?;
                 *info = Some((can_mint, price, royalty));
+                Self::deposit_event(RawEvent::ClassInfoUpdated(class_id, can_mint, price, royalty));
 
                  Ok(())
             })?;
@@ -147,13 +454,14 @@ decl_module! {
         }
 
         #[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-        pub fn set_royalty(origin, class_id: T::ClassId, royalty: u32) -> DispatchResult {
+        pub fn set_royalty(origin, class_id: ClassIdOf<T>, royalty: u32) -> DispatchResult {
             Self::ensure_class_owner(origin, class_id)?;
             ensure!(royalty < 100, Error::<T>::InvalidRoyalty);
 
             Info::<T>::try_mutate(class_id, |info| -> DispatchResult {
                 let (can_mint, price, _) = info.ok_or(Error::<T>::InvalidClassId)?;
                 *info = Some((can_mint, price, royalty));
+                Self::deposit_event(RawEvent::ClassInfoUpdated(class_id, can_mint, price, royalty));
 
                 Ok(())
             })?;
@@ -162,125 +470,549 @@ decl_module! {
         }
 
         #[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-        pub fn mint_nft_token(origin, class_id: T::ClassId, metadata: orml_nft::CID, data: <T as orml_nft::Trait>::TokenData) -> DispatchResult {
+        pub fn mint_nft_token(origin, class_id: ClassIdOf<T>, metadata: orml_nft::CID, data: TokenDataOf<T>) -> DispatchResult {
             let who = ensure_signed(origin)?;
-            let min = T::Currency::minimum_balance();
-            let minter_balance = T::Currency::total_balance(&who);
-
-            let (can_mint, price, _) = Self::info(class_id).ok_or(Error::<T>::InvalidClassId)?;
-
-            ensure!(can_mint, Error::<T>::CantMint);
-
-            // ensure minter has enough funds for minting
-            ensure!(minter_balance > price, Error::<T>::NotEnoughFunds);
+            let quote = Self::quote_mint(class_id, Some(&who))?;
+            let (_, _, royalty) = Self::info(class_id).ok_or(Error::<T>::InvalidClassId)?;
 
-            // ensure minter has mininum balance after minting
-            ensure!(minter_balance-price > min, Error::<T>::BalanceLessThanMininum);
+            let data = T::DataPolicy::token_data(royalty, data);
+            let token_id = T::Nft::mint(&who, class_id, metadata, data)?;
 
-            let token_id = orml_nft::Module::<T>::mint(&who, class_id, metadata, data)?;
+            T::Currency::transfer(&who, &quote.class_owner, quote.price, ExistenceRequirement::KeepAlive)?;
 
-            let class_info = orml_nft::Module::<T>::classes(class_id).ok_or(Error::<T>::InvalidClassId)?;
-            T::Currency::transfer(&who, &class_info.owner, price, ExistenceRequirement::KeepAlive)?;
-
-            Self::deposit_event(RawEvent::OrmlNftTokenMinted(who, token_id));
+            Self::deposit_event(RawEvent::TokenMinted(who, class_id, token_id, quote.price));
 
             Ok(())
         }
 
         #[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-        pub fn nft_transfer(origin, dest: <T::Lookup as StaticLookup>::Source, token_class_id: T::ClassId, token_id: T::TokenId) -> DispatchResult {
+        pub fn nft_transfer(origin, dest: <T::Lookup as StaticLookup>::Source, token_class_id: ClassIdOf<T>, token_id: TokenIdOf<T>) -> DispatchResult {
             let who = ensure_signed(origin)?;
             let to: T::AccountId = T::Lookup::lookup(dest)?;
+            Self::ensure_transferable(token_class_id, token_id)?;
 
             // don't we need to send royalties for an nft_transfer??
             // Self::send_royalties(&who, token_class_id)?;
 
-            orml_nft::Module::<T>::transfer(&who, &to, (token_class_id, token_id))?;
+            // any sale of the token is removed by the `OnNftTransfer` hook
+            T::Nft::transfer(&who, &to, (token_class_id, token_id))?;
 
-            // we need to delete a sale if it exists because the transfer means there is now a new owner of the token
-            Sales::<T>::remove(token_class_id, token_id);
-            Self::deposit_event(RawEvent::OrmlNftTokenTransferred(who, to, token_class_id, token_id));
+            Self::deposit_event(RawEvent::TokenTransferred(who, to, token_class_id, token_id));
             Ok(())
         }
 
         #[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-        pub fn create_sale(origin, class_id: T::ClassId, token_id: T::TokenId, price: BalanceOf<T>) -> DispatchResult {
-            Self::ensure_token_owner(origin, (class_id, token_id))?;
+        pub fn create_sale(origin, class_id: ClassIdOf<T>, token_id: TokenIdOf<T>, price: BalanceOf<T>) -> DispatchResult {
+            let seller = Self::ensure_token_owner(origin, (class_id, token_id))?;
+            Self::ensure_transferable(class_id, token_id)?;
             Sales::<T>::insert(class_id, token_id, price);
-            Self::deposit_event(RawEvent::TokenSaleCreated(class_id, token_id));
+            Self::deposit_event(RawEvent::TokenSaleCreated(seller, class_id, token_id, price));
             Ok(())
         }
 
         #[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-        pub fn delete_sale(origin, class_id: T::ClassId, token_id: T::TokenId) -> DispatchResult {
+        pub fn delete_sale(origin, class_id: ClassIdOf<T>, token_id: TokenIdOf<T>) -> DispatchResult {
             ensure!(Sales::<T>::contains_key(class_id, token_id), Error::<T>::TokenNotForSale);
-            Self::ensure_token_owner(origin, (class_id, token_id))?;
+            let seller = Self::ensure_token_owner(origin, (class_id, token_id))?;
             Sales::<T>::remove(class_id, token_id);
-            Self::deposit_event(RawEvent::TokenSaleDeleted(class_id, token_id));
+            Self::deposit_event(RawEvent::TokenSaleDeleted(seller, class_id, token_id));
             Ok(())
         }
 
-        #[weight = 10_000 + T::DbWeight::get().reads_writes(1,1)]
-        pub fn buy(origin, class_id: T::ClassId, token_id: T::TokenId) -> DispatchResult {
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(9, 8)]
+        #[transactional]
+        pub fn buy(origin, class_id: ClassIdOf<T>, token_id: TokenIdOf<T>) -> DispatchResult {
             ensure!(Sales::<T>::contains_key(class_id, token_id), Error::<T>::TokenNotForSale);
             let buyer = ensure_signed(origin)?;
-            let token_info = orml_nft::Module::<T>::tokens(class_id, token_id).ok_or(Error::<T>::TokenNotFound)?;
-            let token_owner = token_info.owner;
-            let buyer_balance = T::Currency::total_balance(&buyer);
-            let sales_price = Sales::<T>::get(class_id, token_id).ok_or(Error::<T>::TokenNotForSale)?;
-            let royalty = Self::calculate_royalty(class_id, sales_price)?;
-            let min = T::Currency::minimum_balance();
-
-            // can't buy your own sale
-            ensure!(buyer != token_owner, Error::<T>::BuyerSellerSame);
-
-            // ensure buyer has the amount for sale
-            ensure!(buyer_balance > sales_price, Error::<T>::NotEnoughFunds);
-
-            // ensure buyer has minimum account balance after sale
-            ensure!(buyer_balance-sales_price > min, Error::<T>::BalanceLessThanMininum);
+            let quote = Self::quote_sale(class_id, token_id, Some(&buyer))?;
+            let token_owner = quote.seller;
 
             //send over funds to seller for purchase to ensure buyer has funds
             T::Currency::transfer(
                 &buyer,
                 &token_owner,
-                sales_price-royalty,
+                quote.seller_receives,
                 ExistenceRequirement::KeepAlive,
             )?;
 
             //send royalties to class owner from the token owner who sold it
-            Self::send_royalties(&buyer, class_id, sales_price)?;
+            Self::send_royalties(&buyer, class_id, quote.royalty)?;
+            Self::charge_platform_fee(&buyer, class_id, quote.price)?;
 
-            //transfer the nft
-            orml_nft::Module::<T>::transfer(&token_owner, &buyer, (class_id, token_id))?;
+            //transfer the nft, which also removes the sale
+            T::Nft::transfer(&token_owner, &buyer, (class_id, token_id))?;
 
-            //remove sale after it's been bought
-            Sales::<T>::remove(class_id, token_id);
+            Self::deposit_event(RawEvent::TokenSaleCompleted(
+                token_owner,
+                buyer,
+                class_id,
+                token_id,
+                quote.price,
+                quote.royalty,
+            ));
+            Ok(())
+        }
+
+        // New calls go below this point, so the call indices signed by wallets don't shift.
+
+        /// Put a token in another token, e.g. gear in a bag, so it moves with that token. Both
+        /// tokens must belong to the caller, so nothing can be pushed into a token held by
+        /// someone else or by the vault.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(6,6)]
+        pub fn nest_nft(origin, class_id: ClassIdOf<T>, token_id: TokenIdOf<T>, parent_class_id: ClassIdOf<T>, parent_token_id: TokenIdOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            Self::ensure_transferable(class_id, token_id)?;
+
+            // nested tokens are checked and unlisted by the `OnNftTransfer` hook
+            T::Nft::nest(&who, (class_id, token_id), (parent_class_id, parent_token_id))?;
 
-            Self::deposit_event(RawEvent::TokenSaleCompleted(buyer, class_id, token_id));
+            Self::deposit_event(RawEvent::TokenNested(who, class_id, token_id, parent_class_id, parent_token_id));
+            Ok(())
+        }
+
+        /// Mint several tokens of a class at once, free of the minting price. Only the class owner
+        /// can batch mint, and either every token is minted or none are.
+        #[weight = 10_000 * (tokens.len() as Weight) + T::DbWeight::get().reads_writes(
+            2 + 2 * tokens.len() as Weight,
+            4 * tokens.len() as Weight,
+        )]
+        #[transactional]
+        pub fn batch_mint(
+            origin,
+            class_id: ClassIdOf<T>,
+            tokens: Vec<(<T::Lookup as StaticLookup>::Source, orml_nft::CID, TokenDataOf<T>)>,
+        ) -> DispatchResult {
+            ensure!(tokens.len() as u32 <= T::MaxBatchSize::get(), Error::<T>::BatchTooLarge);
+            Self::ensure_class_owner(origin, class_id)?;
+
+            let (can_mint, _, royalty) = Self::info(class_id).ok_or(Error::<T>::InvalidClassId)?;
+            ensure!(can_mint, Error::<T>::CantMint);
+
+            for (dest, metadata, data) in tokens {
+                let to: T::AccountId = T::Lookup::lookup(dest)?;
+                let data = T::DataPolicy::token_data(royalty, data);
+                let token_id = T::Nft::mint(&to, class_id, metadata, data)?;
+
+                Self::deposit_event(RawEvent::TokenMinted(to, class_id, token_id, Zero::zero()));
+            }
+
+            Ok(())
+        }
+
+        /// Transfer several tokens owned by the caller at once. Either every token is
+        /// transferred or none are.
+        #[weight = 10_000 * (tokens.len() as Weight) + T::DbWeight::get().reads_writes(
+            tokens.len() as Weight,
+            4 * tokens.len() as Weight,
+        )]
+        #[transactional]
+        pub fn batch_transfer(
+            origin,
+            tokens: Vec<(<T::Lookup as StaticLookup>::Source, ClassIdOf<T>, TokenIdOf<T>)>,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            ensure!(tokens.len() as u32 <= T::MaxBatchSize::get(), Error::<T>::BatchTooLarge);
+
+            for (dest, class_id, token_id) in tokens {
+                let to: T::AccountId = T::Lookup::lookup(dest)?;
+                Self::ensure_transferable(class_id, token_id)?;
+
+                // any sale of the token is removed by the `OnNftTransfer` hook
+                T::Nft::transfer(&who, &to, (class_id, token_id))?;
+
+                Self::deposit_event(RawEvent::TokenTransferred(who.clone(), to, class_id, token_id));
+            }
+
+            Ok(())
+        }
+
+        /// Burn a token. The owner can always burn their token; the class owner can also burn
+        /// tokens of a soulbound class, e.g. to revoke a credential.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(3,4)]
+        pub fn burn(origin, class_id: ClassIdOf<T>, token_id: TokenIdOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let owner = T::Nft::owner((class_id, token_id)).ok_or(Error::<T>::TokenNotFound)?;
+
+            if who != owner {
+                let class_owner = T::Nft::class_owner(class_id).ok_or(Error::<T>::InvalidClassId)?;
+                ensure!(who == class_owner && Self::soulbound(class_id), Error::<T>::InvalidPermission);
+            }
+
+            // frozen tokens are rejected and any sale of the token is removed by the `OnNftBurn`
+            // hook
+            T::Nft::burn(&owner, (class_id, token_id))?;
+
+            Self::deposit_event(RawEvent::TokenBurned(owner, class_id, token_id));
+            Ok(())
+        }
+
+        /// Stop every token of a class from being transferred, listed, sold or burned. Callable
+        /// by the class owner or `FreezeOrigin`, which can take over a freeze of the class owner.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(2,1)]
+        pub fn freeze_class(origin, class_id: ClassIdOf<T>) -> DispatchResult {
+            let freezer = Self::ensure_freezer(origin, class_id)?;
+            ensure!(Self::frozen_class(class_id).map_or(true, |by| by < freezer), Error::<T>::AlreadyFrozen);
+            FrozenClasses::<T>::insert(class_id, freezer);
+            Self::deposit_event(RawEvent::ClassFrozen(class_id));
+            Ok(())
+        }
+
+        /// Lift a freeze of a class. Only `FreezeOrigin` can lift its own freezes.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(2,1)]
+        pub fn thaw_class(origin, class_id: ClassIdOf<T>) -> DispatchResult {
+            let freezer = Self::ensure_freezer(origin, class_id)?;
+            let frozen_by = Self::frozen_class(class_id).ok_or(Error::<T>::NotFrozen)?;
+            ensure!(frozen_by <= freezer, Error::<T>::InvalidPermission);
+            FrozenClasses::<T>::remove(class_id);
+            Self::deposit_event(RawEvent::ClassThawed(class_id));
+            Ok(())
+        }
+
+        /// Stop a single token from being transferred, listed, sold or burned. Callable by the
+        /// class owner or `FreezeOrigin`, which can take over a freeze of the class owner.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(3,1)]
+        pub fn freeze_token(origin, class_id: ClassIdOf<T>, token_id: TokenIdOf<T>) -> DispatchResult {
+            let freezer = Self::ensure_freezer(origin, class_id)?;
+            ensure!(T::Nft::owner((class_id, token_id)).is_some(), Error::<T>::TokenNotFound);
+            ensure!(
+                Self::frozen_token(class_id, token_id).map_or(true, |by| by < freezer),
+                Error::<T>::AlreadyFrozen
+            );
+            FrozenTokens::<T>::insert(class_id, token_id, freezer);
+            Self::deposit_event(RawEvent::TokenFrozen(class_id, token_id));
+            Ok(())
+        }
+
+        /// Lift a freeze of a token. Only `FreezeOrigin` can lift its own freezes.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(2,1)]
+        pub fn thaw_token(origin, class_id: ClassIdOf<T>, token_id: TokenIdOf<T>) -> DispatchResult {
+            let freezer = Self::ensure_freezer(origin, class_id)?;
+            let frozen_by = Self::frozen_token(class_id, token_id).ok_or(Error::<T>::NotFrozen)?;
+            ensure!(frozen_by <= freezer, Error::<T>::InvalidPermission);
+            FrozenTokens::<T>::remove(class_id, token_id);
+            Self::deposit_event(RawEvent::TokenThawed(class_id, token_id));
+            Ok(())
+        }
+
+        /// Lock a token in the vault and issue `shares` fungible shares of it to the owner. Anyone
+        /// can later buy the token out for `buyout_price`.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(5,6)]
+        pub fn fractionalize(origin, class_id: ClassIdOf<T>, token_id: TokenIdOf<T>, shares: u64, buyout_price: BalanceOf<T>) -> DispatchResult {
+            let who = Self::ensure_token_owner(origin, (class_id, token_id))?;
+            ensure!(shares > 0, Error::<T>::InvalidShares);
+
+            // any sale of the token is removed by the `OnNftTransfer` hook, which also rejects
+            // soulbound and frozen tokens
+            T::Nft::transfer(&who, &Self::vault_account(), (class_id, token_id))?;
+
+            Fractions::<T>::insert(class_id, token_id, Fraction {
+                issuer: who.clone(),
+                total_shares: shares,
+                holders: 1,
+                buyout_price,
+            });
+            Shares::<T>::insert((class_id, token_id), &who, shares);
+
+            Self::deposit_event(RawEvent::TokenFractionalized(who, class_id, token_id, shares, buyout_price));
+            Ok(())
+        }
+
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(3,3)]
+        pub fn transfer_shares(origin, dest: <T::Lookup as StaticLookup>::Source, class_id: ClassIdOf<T>, token_id: TokenIdOf<T>, amount: u64) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let to: T::AccountId = T::Lookup::lookup(dest)?;
+            ensure!(amount > 0, Error::<T>::InvalidShares);
+
+            Fractions::<T>::try_mutate(class_id, token_id, |fraction| -> DispatchResult {
+                let fraction = fraction.as_mut().ok_or(Error::<T>::TokenNotFractionalized)?;
+                let token = (class_id, token_id);
+
+                let balance = Self::shares(token, &who);
+                ensure!(balance >= amount, Error::<T>::NotEnoughShares);
+                if who == to {
+                    return Ok(());
+                }
+
+                let dest_balance = Self::shares(token, &to);
+                let joins = dest_balance == 0;
+                let leaves = balance == amount;
+                // handing over a whole balance to a new holder keeps the count the same
+                if joins && !leaves {
+                    ensure!(fraction.holders < T::MaxShareHolders::get(), Error::<T>::TooManyShareHolders);
+                    fraction.holders += 1;
+                } else if leaves && !joins {
+                    fraction.holders -= 1;
+                }
+                if leaves {
+                    Shares::<T>::remove(token, &who);
+                } else {
+                    Shares::<T>::insert(token, &who, balance - amount);
+                }
+                Shares::<T>::insert(token, &to, dest_balance.saturating_add(amount));
+
+                Ok(())
+            })?;
+
+            Self::deposit_event(RawEvent::SharesTransferred(who, to, class_id, token_id, amount));
+            Ok(())
+        }
+
+        /// Give back every share of a fractionalized token to take the token out of the vault.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(5,6)]
+        pub fn redeem(origin, class_id: ClassIdOf<T>, token_id: TokenIdOf<T>) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let fraction = Self::fractions(class_id, token_id).ok_or(Error::<T>::TokenNotFractionalized)?;
+            ensure!(Self::shares((class_id, token_id), &who) == fraction.total_shares, Error::<T>::NotEnoughShares);
+
+            T::Nft::transfer(&Self::vault_account(), &who, (class_id, token_id))?;
+
+            Fractions::<T>::remove(class_id, token_id);
+            Shares::<T>::remove((class_id, token_id), &who);
+
+            Self::deposit_event(RawEvent::TokenRedeemed(who, class_id, token_id));
+            Ok(())
+        }
+
+        /// Buy a fractionalized token out of the vault for its buyout price. The royalty is paid
+        /// to the class owner, the platform fee to the marketplace and the rest split between the
+        /// other share holders pro rata. Payouts too small to open the account of a holder with
+        /// no funds go to `OnPlatformFee` instead.
+        #[weight = 10_000 + T::DbWeight::get().reads_writes(
+            6 + 2 * T::MaxShareHolders::get() as Weight,
+            6 + 2 * T::MaxShareHolders::get() as Weight,
+        )]
+        #[transactional]
+        pub fn buyout(origin, class_id: ClassIdOf<T>, token_id: TokenIdOf<T>) -> DispatchResult {
+            let buyer = ensure_signed(origin)?;
+            let fraction = Self::fractions(class_id, token_id).ok_or(Error::<T>::TokenNotFractionalized)?;
+            let token = (class_id, token_id);
+            let price = fraction.buyout_price;
+            Self::ensure_transferable(class_id, token_id)?;
+            Self::ensure_can_pay(&buyer, price)?;
+
+            let royalty = Self::calculate_royalty(class_id, price)?;
+            Self::send_royalties(&buyer, class_id, royalty)?;
+            let platform_fee = Self::charge_platform_fee(&buyer, class_id, price)?;
+
+            let proceeds: u128 = price.saturating_sub(royalty).saturating_sub(platform_fee).saturated_into();
+            let total_shares = fraction.total_shares as u128;
+            let mut dust: BalanceOf<T> = Zero::zero();
+            for (holder, shares) in Shares::<T>::iter_prefix(token).collect::<Vec<_>>() {
+                if holder == buyer {
+                    continue;
+                }
+
+                let payout: BalanceOf<T> = multiply_by_rational(proceeds, shares as u128, total_shares)
+                    .map_err(|_| Error::<T>::PayoutOverflow)?
+                    .saturated_into();
+                if payout < T::Currency::minimum_balance() && T::Currency::total_balance(&holder).is_zero() {
+                    dust = dust.saturating_add(payout);
+                    continue;
+                }
+                T::Currency::transfer(&buyer, &holder, payout, ExistenceRequirement::KeepAlive)?;
+            }
+
+            if !dust.is_zero() {
+                let imbalance = T::Currency::withdraw(
+                    &buyer,
+                    dust,
+                    WithdrawReason::Transfer.into(),
+                    ExistenceRequirement::KeepAlive,
+                )?;
+                T::OnPlatformFee::on_unbalanced(imbalance);
+            }
+
+            Shares::<T>::remove_prefix(token);
+            Fractions::<T>::remove(class_id, token_id);
+            T::Nft::transfer(&Self::vault_account(), &buyer, token)?;
+
+            Self::deposit_event(RawEvent::TokenBoughtOut(buyer, class_id, token_id, price, royalty));
             Ok(())
         }
     }
 }
 
 impl<T: Trait> Module<T> {
-    fn send_royalties(who: &T::AccountId, class_id: T::ClassId, price: BalanceOf<T>) -> DispatchResult {
-        let class_info = orml_nft::Module::<T>::classes(class_id).ok_or(Error::<T>::InvalidClassId)?;
-        let royalty: BalanceOf<T> = Self::calculate_royalty(class_id, price)?;
+    /// Page through the tokens currently for sale.
+    ///
+    /// Listings are returned in storage order, which is stable for a given block, so callers
+    /// paging with `offset` should pin the block they query at.
+    pub fn listings(filter: ListingFilterOf<T>, offset: u32, limit: u32) -> Vec<ListingOf<T>> {
+        let sales: Box<dyn Iterator<Item = (ClassIdOf<T>, TokenIdOf<T>, BalanceOf<T>)>> =
+            match filter.class_id {
+                Some(class_id) => Box::new(
+                    Sales::<T>::iter_prefix(class_id)
+                        .map(move |(token_id, price)| (class_id, token_id, price)),
+                ),
+                None => Box::new(Sales::<T>::iter()),
+            };
+
+        sales
+            .filter(|(class_id, token_id, _)| Self::ensure_transferable(*class_id, *token_id).is_ok())
+            .filter_map(|(class_id, token_id, price)| {
+                let seller = T::Nft::owner((class_id, token_id))?;
+                let royalty = Self::calculate_royalty(class_id, price).ok()?;
+                Some(Listing { class_id, token_id, seller, price, royalty })
+            })
+            .filter(|listing| filter.matches(listing))
+            .skip(offset as usize)
+            .take(limit as usize)
+            .collect()
+    }
+
+    /// Work out what `buy` would charge for a listed token and how the price is split.
+    ///
+    /// When `buyer` is given, this also runs the same balance checks as `buy`, so a failing
+    /// purchase can be predicted before it is signed.
+    pub fn quote_sale(
+        class_id: ClassIdOf<T>,
+        token_id: TokenIdOf<T>,
+        buyer: Option<&T::AccountId>,
+    ) -> Result<SaleQuoteOf<T>, DispatchError> {
+        let seller = T::Nft::owner((class_id, token_id)).ok_or(Error::<T>::TokenNotFound)?;
+        let price = Sales::<T>::get(class_id, token_id).ok_or(Error::<T>::TokenNotForSale)?;
+        Self::ensure_transferable(class_id, token_id)?;
+        let royalty = Self::calculate_royalty(class_id, price)?;
+        let royalty_recipient = T::Nft::class_owner(class_id).ok_or(Error::<T>::InvalidClassId)?;
+        let platform_fee = Self::calculate_platform_fee(price);
+
+        if let Some(buyer) = buyer {
+            // can't buy your own sale
+            ensure!(*buyer != seller, Error::<T>::BuyerSellerSame);
+            Self::ensure_can_pay(buyer, price)?;
+        }
+
+        Ok(SaleQuote {
+            seller,
+            royalty_recipient,
+            price,
+            royalty,
+            platform_fee,
+            seller_receives: price.saturating_sub(royalty).saturating_sub(platform_fee),
+        })
+    }
+
+    /// Work out what `mint_nft_token` would charge in `class_id`.
+    ///
+    /// When `minter` is given, this also runs the same balance checks as `mint_nft_token`.
+    pub fn quote_mint(
+        class_id: ClassIdOf<T>,
+        minter: Option<&T::AccountId>,
+    ) -> Result<MintQuoteOf<T>, DispatchError> {
+        let (can_mint, price, _) = Self::info(class_id).ok_or(Error::<T>::InvalidClassId)?;
+        ensure!(can_mint, Error::<T>::CantMint);
+        let class_owner = T::Nft::class_owner(class_id).ok_or(Error::<T>::InvalidClassId)?;
+
+        if let Some(minter) = minter {
+            Self::ensure_can_pay(minter, price)?;
+        }
+
+        Ok(MintQuote {
+            class_owner,
+            price,
+        })
+    }
+
+    /// Everything the marketplace knows about `class_id`.
+    pub fn class_info(class_id: ClassIdOf<T>) -> Option<ClassDetailsOf<T>> {
+        let (can_mint, mint_price, royalty) = Self::info(class_id)?;
+
+        Some(ClassDetails {
+            owner: T::Nft::class_owner(class_id)?,
+            can_mint,
+            mint_price,
+            royalty,
+            soulbound: Self::soulbound(class_id),
+        })
+    }
+
+    /// Ensure a token may change owner: its class isn't soulbound and neither the class nor the
+    /// token is frozen.
+    fn ensure_transferable(class_id: ClassIdOf<T>, token_id: TokenIdOf<T>) -> DispatchResult {
+        ensure!(!Self::soulbound(class_id), Error::<T>::TokenIsSoulbound);
+        Self::ensure_not_frozen(class_id, token_id)
+    }
+
+    /// Ensure neither the class nor the token is frozen.
+    fn ensure_not_frozen(class_id: ClassIdOf<T>, token_id: TokenIdOf<T>) -> DispatchResult {
+        ensure!(Self::frozen_class(class_id).is_none(), Error::<T>::ClassIsFrozen);
+        ensure!(Self::frozen_token(class_id, token_id).is_none(), Error::<T>::TokenIsFrozen);
+        Ok(())
+    }
+
+    /// Ensure `origin` is `FreezeOrigin` or the owner of `class_id`, and return which.
+    fn ensure_freezer(origin: T::Origin, class_id: ClassIdOf<T>) -> Result<FrozenBy, DispatchError> {
+        match T::FreezeOrigin::try_origin(origin) {
+            Ok(_) => {
+                ensure!(T::Nft::class_owner(class_id).is_some(), Error::<T>::InvalidClassId);
+                Ok(FrozenBy::FreezeOrigin)
+            }
+            Err(origin) => Self::ensure_class_owner(origin, class_id).map(|_| FrozenBy::ClassOwner),
+        }
+    }
+
+    /// The account holding fractionalized tokens.
+    pub fn vault_account() -> T::AccountId {
+        T::ModuleId::get().into_account()
+    }
+
+    /// Ensure `who` can pay `price` and still keep the minimum balance afterwards.
+    fn ensure_can_pay(who: &T::AccountId, price: BalanceOf<T>) -> DispatchResult {
+        let balance = T::Currency::total_balance(who);
+
+        // ensure payer has enough funds
+        ensure!(balance > price, Error::<T>::NotEnoughFunds);
+
+        // ensure payer has mininum balance after paying
+        ensure!(balance - price > T::Currency::minimum_balance(), Error::<T>::BalanceLessThanMininum);
+
+        Ok(())
+    }
+
+    fn send_royalties(who: &T::AccountId, class_id: ClassIdOf<T>, royalty: BalanceOf<T>) -> DispatchResult {
+        let class_owner = T::Nft::class_owner(class_id).ok_or(Error::<T>::InvalidClassId)?;
 
         T::Currency::transfer(
             who,
-            &class_info.owner,
+            &class_owner,
             royalty,
             ExistenceRequirement::KeepAlive,
         )?;
 
-        Self::deposit_event(RawEvent::RoyaltySent(who.clone(), royalty));
+        Self::deposit_event(RawEvent::RoyaltySent(who.clone(), class_owner, class_id, royalty));
 
         Ok(())
     }
 
-    fn calculate_royalty(class_id: T::ClassId, price: BalanceOf<T>) -> Result<BalanceOf<T>, DispatchError>  {
+    /// Take the platform fee of a sale for `price` from `who` and hand it to `OnPlatformFee`.
+    fn charge_platform_fee(
+        who: &T::AccountId,
+        class_id: ClassIdOf<T>,
+        price: BalanceOf<T>,
+    ) -> Result<BalanceOf<T>, DispatchError> {
+        let fee = Self::calculate_platform_fee(price);
+        if fee.is_zero() {
+            return Ok(fee);
+        }
+
+        let imbalance = T::Currency::withdraw(
+            who,
+            fee,
+            WithdrawReason::Transfer.into(),
+            ExistenceRequirement::KeepAlive,
+        )?;
+        T::OnPlatformFee::on_unbalanced(imbalance);
+
+        Self::deposit_event(RawEvent::PlatformFeePaid(who.clone(), class_id, fee));
+
+        Ok(fee)
+    }
+
+    fn calculate_platform_fee(price: BalanceOf<T>) -> BalanceOf<T> {
+        T::PlatformFee::get().mul_floor(price)
+    }
+
+    fn calculate_royalty(class_id: ClassIdOf<T>, price: BalanceOf<T>) -> Result<BalanceOf<T>, DispatchError>  {
         let (_, _, royalty) = Info::<T>::get(class_id).ok_or(Error::<T>::InvalidClassId)?;
         let royalty_fee: BalanceOf<T> = Permill::from_percent(royalty).mul_floor(price);
         Ok(royalty_fee)
@@ -288,23 +1020,46 @@ impl<T: Trait> Module<T> {
 
     fn ensure_class_owner(
         origin: T::Origin,
-        class_id: T::ClassId,
+        class_id: ClassIdOf<T>,
     ) -> Result<T::AccountId, DispatchError> {
         let who = ensure_signed(origin)?;
-        let class_info =
-            orml_nft::Module::<T>::classes(class_id).ok_or(Error::<T>::InvalidClassId)?;
+        let class_owner = T::Nft::class_owner(class_id).ok_or(Error::<T>::InvalidClassId)?;
 
-        ensure!(who == class_info.owner, Error::<T>::InvalidPermission);
+        ensure!(who == class_owner, Error::<T>::InvalidPermission);
 
         Ok(who)
     }
 
     fn ensure_token_owner(
         origin: T::Origin,
-        token: (T::ClassId, T::TokenId),
+        token: (ClassIdOf<T>, TokenIdOf<T>),
     ) -> Result<T::AccountId, DispatchError> {
         let who = ensure_signed(origin)?;
-        ensure!(orml_nft::Module::<T>::tokens_by_owner(who.clone(), token).is_some(), Error::<T>::TokenNotOwned);
+        ensure!(T::Nft::is_owner(&who, token), Error::<T>::TokenNotOwned);
         Ok(who)
     }
 }
+
+impl<T: Trait> OnNftTransfer<T::AccountId, ClassIdOf<T>, TokenIdOf<T>> for Module<T> {
+    fn on_transfer(
+        _from: &T::AccountId,
+        _to: &T::AccountId,
+        token: (ClassIdOf<T>, TokenIdOf<T>),
+    ) -> DispatchResult {
+        Self::ensure_transferable(token.0, token.1)?;
+
+        // a listing is made by the owner, so it can't outlive a change of owner
+        Sales::<T>::remove(token.0, token.1);
+        Ok(())
+    }
+}
+
+impl<T: Trait> OnNftBurn<T::AccountId, ClassIdOf<T>, TokenIdOf<T>> for Module<T> {
+    fn on_burn(_owner: &T::AccountId, token: (ClassIdOf<T>, TokenIdOf<T>)) -> DispatchResult {
+        // e.g. so a stolen item can't be destroyed while a dispute over it is investigated
+        Self::ensure_not_frozen(token.0, token.1)?;
+
+        Sales::<T>::remove(token.0, token.1);
+        Ok(())
+    }
+}
@@ -1,21 +1,27 @@
 use crate::{Module, Trait};
-use frame_support::{impl_outer_origin, impl_outer_event, parameter_types, weights::Weight};
+use frame_support::{impl_outer_origin, impl_outer_event, parameter_types, traits::Get, weights::Weight};
 use frame_system as system;
 use sp_core::H256;
 use sp_runtime::{
     testing::Header,
     traits::{BlakeTwo256, IdentityLookup},
-    Perbill,
+    ModuleId, Perbill, Permill,
 };
+use std::cell::RefCell;
 
 impl_outer_origin! {
     pub enum Origin for Test {}
 }
 
+mod royalty_nft {
+    pub use crate::Event;
+}
+
 impl_outer_event! {
 	pub enum Event for Test {
         frame_system<T>,
         pallet_balances<T>,
+        royalty_nft<T>,
 	}
 }
 
@@ -41,7 +47,7 @@ impl system::Trait for Test {
     type AccountId = u64;
     type Lookup = IdentityLookup<Self::AccountId>;
     type Header = Header;
-    type Event = ();
+    type Event = Event;
     type BlockHashCount = BlockHashCount;
     type MaximumBlockWeight = MaximumBlockWeight;
     type DbWeight = ();
@@ -58,13 +64,27 @@ impl system::Trait for Test {
     type SystemWeightInfo = ();
 }
 
-parameter_types! {
-	pub const ExistentialDeposit: u64 = 1;
+thread_local! {
+    static EXISTENTIAL_DEPOSIT: RefCell<u64> = RefCell::new(1);
+}
+
+pub struct ExistentialDeposit;
+impl Get<u64> for ExistentialDeposit {
+    fn get() -> u64 {
+        EXISTENTIAL_DEPOSIT.with(|deposit| *deposit.borrow())
+    }
 }
+
+impl ExistentialDeposit {
+    pub fn set(deposit: u64) {
+        EXISTENTIAL_DEPOSIT.with(|existential_deposit| *existential_deposit.borrow_mut() = deposit);
+    }
+}
+
 impl pallet_balances::Trait for Test {
 	type MaxLocks = ();
 	type Balance = u64;
-	type Event = ();
+	type Event = Event;
 	type DustRemoval = ();
 	type ExistentialDeposit = ExistentialDeposit;
 	type AccountStore = System;
@@ -76,14 +96,50 @@ impl orml_nft::Trait for Test {
 	type TokenId = u32;
 	type ClassData = ();
 	type TokenData = ();
+	type MaxNestingDepth = MaxNestingDepth;
+	type OnTransfer = LootNft;
+	type OnMint = ();
+	type OnBurn = LootNft;
+}
+
+parameter_types! {
+    pub const MaxBatchSize: u32 = 3;
+    pub const MaxNestingDepth: u32 = 2;
+    pub const LootModuleId: ModuleId = ModuleId(*b"loot/nft");
+    pub const MaxShareHolders: u32 = 2;
+}
+
+thread_local! {
+    static PLATFORM_FEE: RefCell<Permill> = RefCell::new(Permill::zero());
+}
+
+pub struct PlatformFee;
+impl Get<Permill> for PlatformFee {
+    fn get() -> Permill {
+        PLATFORM_FEE.with(|fee| *fee.borrow())
+    }
+}
+
+impl PlatformFee {
+    pub fn set(fee: Permill) {
+        PLATFORM_FEE.with(|platform_fee| *platform_fee.borrow_mut() = fee);
+    }
 }
 
 impl Trait for Test {
-    type Event = ();
+    type Event = Event;
     type Currency = Balances;
+    type Nft = NFT;
+    type MaxBatchSize = MaxBatchSize;
+    type FreezeOrigin = system::EnsureRoot<u64>;
+    type ModuleId = LootModuleId;
+    type MaxShareHolders = MaxShareHolders;
+    type DataPolicy = ();
+    type PlatformFee = PlatformFee;
+    type OnPlatformFee = ();
 }
 
-type System = frame_system::Module<Test>;
+pub type System = frame_system::Module<Test>;
 pub type Balances = pallet_balances::Module<Test>;
 
 pub type LootNft = Module<Test>;
@@ -102,4 +158,15 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 
     t.execute_with(|| System::set_block_number(1) );
     t
+}
+
+/// The `royalty_nft` events deposited so far, oldest first.
+pub fn loot_events() -> Vec<crate::Event<Test>> {
+    System::events()
+        .into_iter()
+        .filter_map(|record| match record.event {
+            Event::royalty_nft(event) => Some(event),
+            _ => None,
+        })
+        .collect()
 }
\ No newline at end of file
@@ -1,13 +1,23 @@
-use crate::{mock::*, Error};
-use frame_support::{assert_noop, assert_ok};
+use crate::{
+    mock::*, ClassDetails, Error, FrozenBy, GenesisClass, GenesisConfig, GenesisToken, Listing,
+    ListingFilter, MintQuote, RawEvent, SaleQuote,
+};
+use frame_support::{assert_noop, assert_ok, traits::ReservableCurrency};
+use orml_nft::TokenOwner;
+use sp_runtime::Permill;
 
 #[test]
 fn create_nft_class() {
     new_test_ext().execute_with(|| {
-        assert_ok!(LootNft::create_nft_class(Origin::signed(100), vec![0], (), 10, 10));
+        assert_ok!(LootNft::create_nft_class(Origin::signed(100), vec![0], (), 10, 10, false));
         assert_eq!(NFT::next_class_id(), 1);
-        assert_ok!(LootNft::create_nft_class(Origin::signed(101), vec![0], (), 10, 10));
+        assert_ok!(LootNft::create_nft_class(Origin::signed(101), vec![0], (), 20, 5, false));
         assert_eq!(NFT::next_class_id(), 2);
+
+        assert_eq!(loot_events(), vec![
+            RawEvent::ClassCreated(100, 0, 10, 10, false),
+            RawEvent::ClassCreated(101, 1, 20, 5, false),
+        ]);
     });
 }
 
@@ -15,11 +25,12 @@ fn create_nft_class() {
 fn mint_nft_token() {
     new_test_ext().execute_with(|| {
         // Can mint token
-        assert_ok!(LootNft::create_nft_class(Origin::signed(100), vec![0], (), 10, 10));
+        assert_ok!(LootNft::create_nft_class(Origin::signed(100), vec![0], (), 10, 10, false));
         assert_ok!(LootNft::mint_nft_token(Origin::signed(200), 0, vec![0], ()));
+        assert_eq!(loot_events().last(), Some(&RawEvent::TokenMinted(200, 0, 0, 10)));
 
         // Can't mint token that is too expensive
-        assert_ok!(LootNft::create_nft_class(Origin::signed(100), vec![0], (), 1000, 10));
+        assert_ok!(LootNft::create_nft_class(Origin::signed(100), vec![0], (), 1000, 10, false));
         assert_noop!(LootNft::mint_nft_token(Origin::signed(200), 1, vec![0], ()), Error::<Test>::NotEnoughFunds);
     });
 }
@@ -27,18 +38,19 @@ fn mint_nft_token() {
 #[test]
 fn transfer_token() {
     new_test_ext().execute_with(|| {
-        assert_ok!(LootNft::create_nft_class(Origin::signed(100), vec![0], (), 10, 10));
+        assert_ok!(LootNft::create_nft_class(Origin::signed(100), vec![0], (), 10, 10, false));
         assert_ok!(LootNft::mint_nft_token(Origin::signed(200), 0, vec![0], ()));
 
-        assert_eq!(NFT::tokens(0, 0).unwrap().owner, 200);
+        assert_eq!(NFT::tokens(0, 0).unwrap().owner, TokenOwner::Account(200));
 
         // Can transfer NFT that they own
         assert_ok!(LootNft::nft_transfer(Origin::signed(200), 100, 0, 0));
-        assert_eq!(NFT::tokens(0, 0).unwrap().owner, 100);
+        assert_eq!(NFT::tokens(0, 0).unwrap().owner, TokenOwner::Account(100));
+        assert_eq!(loot_events().last(), Some(&RawEvent::TokenTransferred(200, 100, 0, 0)));
 
         // Can't transfer NFT they do not own
         assert_noop!(LootNft::nft_transfer(Origin::signed(200), 101, 0, 0), orml_nft::Error::<Test>::NoPermission);
-        assert_eq!(NFT::tokens(0, 0).unwrap().owner, 100);
+        assert_eq!(NFT::tokens(0, 0).unwrap().owner, TokenOwner::Account(100));
 
         // Can't transfer NFT that does not exist
         assert_noop!(LootNft::nft_transfer(Origin::signed(200), 101, 0, 1), orml_nft::Error::<Test>::TokenNotFound);
@@ -54,7 +66,7 @@ fn transfer_token() {
 fn set_info() {
     new_test_ext().execute_with(|| {
         // Info set properly on class creation
-        assert_ok!(LootNft::create_nft_class(Origin::signed(100), vec![0], (), 10, 10));
+        assert_ok!(LootNft::create_nft_class(Origin::signed(100), vec![0], (), 10, 10, false));
         assert_eq!(LootNft::info(0), Some((true, 10, 10)));
 
         // Can set mintable
@@ -69,6 +81,14 @@ fn set_info() {
         assert_ok!(LootNft::set_royalty(Origin::signed(100), 0, 20));
         assert_eq!(LootNft::info(0), Some((false, 20, 20)));
 
+        // Every change is reported with the full class info
+        assert_eq!(loot_events(), vec![
+            RawEvent::ClassCreated(100, 0, 10, 10, false),
+            RawEvent::ClassInfoUpdated(0, false, 10, 10),
+            RawEvent::ClassInfoUpdated(0, false, 20, 10),
+            RawEvent::ClassInfoUpdated(0, false, 20, 20),
+        ]);
+
         // Non class owner can't set mintable
         assert_noop!(LootNft::set_mintable(Origin::signed(200), 0, true), Error::<Test>::InvalidPermission);
         assert_eq!(LootNft::info(0), Some((false, 20, 20)));
@@ -97,13 +117,20 @@ fn buy_nft() {
         let sales_price = 20;
         let royalty_amount = 2; // 10% of price
 
-        assert_ok!(LootNft::create_nft_class(Origin::signed(300), vec![0], (), minting_price, 10));
+        assert_ok!(LootNft::create_nft_class(Origin::signed(300), vec![0], (), minting_price, 10, false));
 
         assert_ok!(LootNft::mint_nft_token(Origin::signed(100), 0, vec![0], ()));
         assert_ok!(LootNft::create_sale(Origin::signed(100), 0, 0, sales_price));
 
         // Can buy
         assert_ok!(LootNft::buy(Origin::signed(200), 0, 0));
+        assert_eq!(loot_events(), vec![
+            RawEvent::ClassCreated(300, 0, minting_price, 10, false),
+            RawEvent::TokenMinted(100, 0, 0, minting_price),
+            RawEvent::TokenSaleCreated(100, 0, 0, sales_price),
+            RawEvent::RoyaltySent(200, 300, 0, royalty_amount),
+            RawEvent::TokenSaleCompleted(100, 200, 0, 0, sales_price, royalty_amount),
+        ]);
 
         // Balances transfered
         assert_eq!(Balances::free_balance(200), buyer_balance - sales_price);
@@ -111,7 +138,7 @@ fn buy_nft() {
         assert_eq!(Balances::free_balance(300), class_creator_balance + minting_price + royalty_amount);
 
         // Token transfered to buyer
-        assert_eq!(NFT::tokens(0, 0).unwrap().owner, 200);
+        assert_eq!(NFT::tokens(0, 0).unwrap().owner, TokenOwner::Account(200));
 
         // Token no longer for sale
         assert_noop!(LootNft::buy(Origin::signed(100), 0, 0), Error::<Test>::TokenNotForSale);
@@ -129,7 +156,7 @@ fn buy_nft() {
 #[test]
 fn delete_sale() {
     new_test_ext().execute_with(|| {
-        assert_ok!(LootNft::create_nft_class(Origin::signed(100), vec![0], (), 10, 10));
+        assert_ok!(LootNft::create_nft_class(Origin::signed(100), vec![0], (), 10, 10, false));
 
         assert_ok!(LootNft::mint_nft_token(Origin::signed(100), 0, vec![0], ()));
         assert_ok!(LootNft::create_sale(Origin::signed(100), 0, 0, 20));
@@ -139,6 +166,7 @@ fn delete_sale() {
 
         // Can remove sale
         assert_ok!(LootNft::delete_sale(Origin::signed(100), 0, 0));
+        assert_eq!(loot_events().last(), Some(&RawEvent::TokenSaleDeleted(100, 0, 0)));
 
         // Can't buy after sale removed
         assert_noop!(LootNft::buy(Origin::signed(200), 0, 0), Error::<Test>::TokenNotForSale);
@@ -146,4 +174,583 @@ fn delete_sale() {
         // Removing sale that doesn't exist is noop
         assert_noop!(LootNft::delete_sale(Origin::signed(100), 0, 0), Error::<Test>::TokenNotForSale);
     });
-}
\ No newline at end of file
+}
+#[test]
+fn listings() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LootNft::create_nft_class(Origin::signed(300), vec![0], (), 10, 10, false));
+        assert_ok!(LootNft::create_nft_class(Origin::signed(300), vec![0], (), 10, 20, false));
+
+        assert_ok!(LootNft::mint_nft_token(Origin::signed(100), 0, vec![0], ()));
+        assert_ok!(LootNft::mint_nft_token(Origin::signed(200), 0, vec![0], ()));
+        assert_ok!(LootNft::mint_nft_token(Origin::signed(100), 1, vec![0], ()));
+        assert_ok!(LootNft::mint_nft_token(Origin::signed(100), 1, vec![0], ()));
+
+        assert_ok!(LootNft::create_sale(Origin::signed(100), 0, 0, 20));
+        assert_ok!(LootNft::create_sale(Origin::signed(200), 0, 1, 50));
+        assert_ok!(LootNft::create_sale(Origin::signed(100), 1, 0, 100));
+
+        // All listings, with royalty computed from the class
+        let mut all = LootNft::listings(Default::default(), 0, 10);
+        all.sort_by_key(|listing| (listing.class_id, listing.token_id));
+        assert_eq!(all, vec![
+            Listing { class_id: 0, token_id: 0, seller: 100, price: 20, royalty: 2 },
+            Listing { class_id: 0, token_id: 1, seller: 200, price: 50, royalty: 5 },
+            Listing { class_id: 1, token_id: 0, seller: 100, price: 100, royalty: 20 },
+        ]);
+
+        // Filter by class
+        let filter = ListingFilter { class_id: Some(1), ..Default::default() };
+        assert_eq!(LootNft::listings(filter, 0, 10), vec![
+            Listing { class_id: 1, token_id: 0, seller: 100, price: 100, royalty: 20 },
+        ]);
+
+        // Filter by seller
+        let filter = ListingFilter { seller: Some(200), ..Default::default() };
+        assert_eq!(LootNft::listings(filter, 0, 10), vec![
+            Listing { class_id: 0, token_id: 1, seller: 200, price: 50, royalty: 5 },
+        ]);
+
+        // Filter by price range
+        let filter = ListingFilter { min_price: Some(30), max_price: Some(100), ..Default::default() };
+        assert_eq!(LootNft::listings(filter, 0, 10).len(), 2);
+
+        // Pages cover every listing exactly once
+        let mut paged = LootNft::listings(Default::default(), 0, 2);
+        assert_eq!(paged.len(), 2);
+        paged.extend(LootNft::listings(Default::default(), 2, 2));
+        paged.sort_by_key(|listing| (listing.class_id, listing.token_id));
+        assert_eq!(paged, all);
+
+        // Transferred tokens are no longer listed
+        assert_ok!(LootNft::nft_transfer(Origin::signed(100), 200, 0, 0));
+        assert_eq!(LootNft::listings(Default::default(), 0, 10).len(), 2);
+    });
+}
+
+#[test]
+fn quote_mint() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LootNft::create_nft_class(Origin::signed(300), vec![0], (), 10, 10, false));
+
+        assert_eq!(LootNft::quote_mint(0, None), Ok(MintQuote { class_owner: 300, price: 10 }));
+        assert_eq!(LootNft::quote_mint(0, Some(&100)), Ok(MintQuote { class_owner: 300, price: 10 }));
+
+        // Predicts the same failures as minting
+        assert_eq!(LootNft::quote_mint(1, None), Err(Error::<Test>::InvalidClassId.into()));
+
+        assert_ok!(LootNft::set_price(Origin::signed(300), 0, 500));
+        assert_eq!(LootNft::quote_mint(0, Some(&100)), Err(Error::<Test>::NotEnoughFunds.into()));
+        assert_noop!(LootNft::mint_nft_token(Origin::signed(100), 0, vec![0], ()), Error::<Test>::NotEnoughFunds);
+
+        assert_ok!(LootNft::set_price(Origin::signed(300), 0, 499));
+        assert_eq!(LootNft::quote_mint(0, Some(&100)), Err(Error::<Test>::BalanceLessThanMininum.into()));
+        assert_noop!(LootNft::mint_nft_token(Origin::signed(100), 0, vec![0], ()), Error::<Test>::BalanceLessThanMininum);
+
+        assert_ok!(LootNft::set_mintable(Origin::signed(300), 0, false));
+        assert_eq!(LootNft::quote_mint(0, Some(&100)), Err(Error::<Test>::CantMint.into()));
+    });
+}
+
+#[test]
+fn quote_sale() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LootNft::create_nft_class(Origin::signed(300), vec![0], (), 10, 10, false));
+        assert_ok!(LootNft::mint_nft_token(Origin::signed(100), 0, vec![0], ()));
+
+        // Can't quote token not for sale
+        assert_eq!(LootNft::quote_sale(0, 0, None), Err(Error::<Test>::TokenNotForSale.into()));
+        assert_eq!(LootNft::quote_sale(0, 1, None), Err(Error::<Test>::TokenNotFound.into()));
+
+        assert_ok!(LootNft::create_sale(Origin::signed(100), 0, 0, 20));
+        let quote = SaleQuote {
+            seller: 100,
+            royalty_recipient: 300,
+            price: 20,
+            royalty: 2,
+            platform_fee: 0,
+            seller_receives: 18,
+        };
+        assert_eq!(LootNft::quote_sale(0, 0, None), Ok(quote.clone()));
+        assert_eq!(LootNft::quote_sale(0, 0, Some(&200)), Ok(quote));
+
+        // Predicts the same failures as buying
+        assert_eq!(LootNft::quote_sale(0, 0, Some(&100)), Err(Error::<Test>::BuyerSellerSame.into()));
+
+        assert_ok!(LootNft::create_sale(Origin::signed(100), 0, 0, 500));
+        assert_eq!(LootNft::quote_sale(0, 0, Some(&200)), Err(Error::<Test>::NotEnoughFunds.into()));
+        assert_noop!(LootNft::buy(Origin::signed(200), 0, 0), Error::<Test>::NotEnoughFunds);
+
+        assert_ok!(LootNft::create_sale(Origin::signed(100), 0, 0, 499));
+        assert_eq!(LootNft::quote_sale(0, 0, Some(&200)), Err(Error::<Test>::BalanceLessThanMininum.into()));
+        assert_noop!(LootNft::buy(Origin::signed(200), 0, 0), Error::<Test>::BalanceLessThanMininum);
+    });
+}
+
+#[test]
+fn listings_cleared_by_nft_hooks() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LootNft::create_nft_class(Origin::signed(300), vec![0], (), 10, 10, false));
+        assert_ok!(LootNft::mint_nft_token(Origin::signed(100), 0, vec![0], ()));
+        assert_ok!(LootNft::mint_nft_token(Origin::signed(100), 0, vec![0], ()));
+        assert_ok!(LootNft::create_sale(Origin::signed(100), 0, 0, 20));
+        assert_ok!(LootNft::create_sale(Origin::signed(100), 0, 1, 20));
+
+        // Transfers that bypass this pallet still remove the listing
+        assert_ok!(NFT::transfer(&100, &200, (0, 0)));
+        assert_eq!(LootNft::sales(0, 0), None);
+        assert_noop!(LootNft::buy(Origin::signed(300), 0, 0), Error::<Test>::TokenNotForSale);
+
+        // So do burns
+        assert_ok!(NFT::burn(&100, (0, 1)));
+        assert_eq!(LootNft::sales(0, 1), None);
+
+        // Failed transfers leave the listing in place
+        assert_ok!(LootNft::create_sale(Origin::signed(200), 0, 0, 20));
+        assert_noop!(NFT::transfer(&100, &300, (0, 0)), orml_nft::Error::<Test>::NoPermission);
+        assert_eq!(LootNft::sales(0, 0), Some(20));
+    });
+}
+
+#[test]
+fn batch_mint() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LootNft::create_nft_class(Origin::signed(300), vec![0], (), 10, 10, false));
+        let class_owner_balance = Balances::free_balance(300);
+
+        // Class owner can mint to several accounts without paying the price
+        assert_ok!(LootNft::batch_mint(Origin::signed(300), 0, vec![(100, vec![1], ()), (200, vec![2], ())]));
+        assert_eq!(NFT::tokens(0, 0).unwrap().owner, TokenOwner::Account(100));
+        assert_eq!(NFT::tokens(0, 1).unwrap().owner, TokenOwner::Account(200));
+        assert_eq!(Balances::free_balance(300), class_owner_balance);
+        assert_eq!(loot_events()[1..], [
+            RawEvent::TokenMinted(100, 0, 0, 0),
+            RawEvent::TokenMinted(200, 0, 1, 0),
+        ]);
+
+        // Only the class owner can batch mint
+        assert_noop!(
+            LootNft::batch_mint(Origin::signed(100), 0, vec![(100, vec![1], ())]),
+            Error::<Test>::InvalidPermission
+        );
+
+        // Batches are bounded
+        assert_noop!(
+            LootNft::batch_mint(Origin::signed(300), 0, vec![(100, vec![1], ()); 4]),
+            Error::<Test>::BatchTooLarge
+        );
+
+        // Minting must be enabled
+        assert_ok!(LootNft::set_mintable(Origin::signed(300), 0, false));
+        assert_noop!(
+            LootNft::batch_mint(Origin::signed(300), 0, vec![(100, vec![1], ())]),
+            Error::<Test>::CantMint
+        );
+    });
+}
+
+#[test]
+fn batch_transfer() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LootNft::create_nft_class(Origin::signed(300), vec![0], (), 10, 10, false));
+        assert_ok!(LootNft::batch_mint(Origin::signed(300), 0, vec![(100, vec![1], ()); 3]));
+        assert_ok!(LootNft::create_sale(Origin::signed(100), 0, 1, 20));
+
+        assert_ok!(LootNft::batch_transfer(Origin::signed(100), vec![(200, 0, 0), (300, 0, 1)]));
+        assert_eq!(NFT::tokens(0, 0).unwrap().owner, TokenOwner::Account(200));
+        assert_eq!(NFT::tokens(0, 1).unwrap().owner, TokenOwner::Account(300));
+        assert_eq!(LootNft::sales(0, 1), None);
+        assert_eq!(loot_events()[5..], [
+            RawEvent::TokenTransferred(100, 200, 0, 0),
+            RawEvent::TokenTransferred(100, 300, 0, 1),
+        ]);
+
+        // A single token not owned makes the whole batch fail
+        assert_noop!(
+            LootNft::batch_transfer(Origin::signed(100), vec![(200, 0, 2), (200, 0, 0)]),
+            orml_nft::Error::<Test>::NoPermission
+        );
+        assert_eq!(NFT::tokens(0, 2).unwrap().owner, TokenOwner::Account(100));
+
+        // Batches are bounded
+        assert_noop!(
+            LootNft::batch_transfer(Origin::signed(100), vec![(200, 0, 2); 4]),
+            Error::<Test>::BatchTooLarge
+        );
+    });
+}
+
+#[test]
+fn soulbound_class() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LootNft::create_nft_class(Origin::signed(300), vec![0], (), 10, 10, true));
+        assert_ok!(LootNft::mint_nft_token(Origin::signed(100), 0, vec![0], ()));
+        assert_ok!(LootNft::mint_nft_token(Origin::signed(100), 0, vec![0], ()));
+        assert_eq!(LootNft::class_info(0), Some(ClassDetails {
+            owner: 300,
+            can_mint: true,
+            mint_price: 10,
+            royalty: 10,
+            soulbound: true,
+        }));
+
+        // Tokens can't move or be listed
+        assert_noop!(LootNft::nft_transfer(Origin::signed(100), 200, 0, 0), Error::<Test>::TokenIsSoulbound);
+        assert_noop!(LootNft::batch_transfer(Origin::signed(100), vec![(200, 0, 0)]), Error::<Test>::TokenIsSoulbound);
+        assert_noop!(LootNft::create_sale(Origin::signed(100), 0, 0, 20), Error::<Test>::TokenIsSoulbound);
+        assert_noop!(NFT::transfer(&100, &200, (0, 0)), Error::<Test>::TokenIsSoulbound);
+
+        // Others can't burn
+        assert_noop!(LootNft::burn(Origin::signed(200), 0, 0), Error::<Test>::InvalidPermission);
+
+        // Owner and issuer can burn
+        assert_ok!(LootNft::burn(Origin::signed(100), 0, 0));
+        assert_ok!(LootNft::burn(Origin::signed(300), 0, 1));
+        assert_eq!(NFT::tokens(0, 0), None);
+        assert_eq!(NFT::tokens(0, 1), None);
+        assert_eq!(loot_events()[3..], [
+            RawEvent::TokenBurned(100, 0, 0),
+            RawEvent::TokenBurned(100, 0, 1),
+        ]);
+    });
+}
+
+#[test]
+fn burn() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LootNft::create_nft_class(Origin::signed(300), vec![0], (), 10, 10, false));
+        assert_ok!(LootNft::mint_nft_token(Origin::signed(100), 0, vec![0], ()));
+        assert_ok!(LootNft::create_sale(Origin::signed(100), 0, 0, 20));
+        assert_eq!(LootNft::class_info(0).map(|info| info.soulbound), Some(false));
+
+        // Issuer can't burn tokens of a transferable class
+        assert_noop!(LootNft::burn(Origin::signed(300), 0, 0), Error::<Test>::InvalidPermission);
+
+        // Owner can burn, which removes the listing
+        assert_ok!(LootNft::burn(Origin::signed(100), 0, 0));
+        assert_eq!(LootNft::sales(0, 0), None);
+
+        // Can't burn token that does not exist
+        assert_noop!(LootNft::burn(Origin::signed(100), 0, 0), Error::<Test>::TokenNotFound);
+    });
+}
+
+#[test]
+fn freeze_token() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LootNft::create_nft_class(Origin::signed(300), vec![0], (), 10, 10, false));
+        assert_ok!(LootNft::mint_nft_token(Origin::signed(100), 0, vec![0], ()));
+        assert_ok!(LootNft::mint_nft_token(Origin::signed(100), 0, vec![0], ()));
+        assert_ok!(LootNft::create_sale(Origin::signed(100), 0, 0, 20));
+
+        // Only the class owner or the freeze origin can freeze
+        assert_noop!(LootNft::freeze_token(Origin::signed(100), 0, 0), Error::<Test>::InvalidPermission);
+        assert_noop!(LootNft::freeze_token(Origin::root(), 0, 5), Error::<Test>::TokenNotFound);
+        assert_noop!(LootNft::thaw_token(Origin::root(), 0, 0), Error::<Test>::NotFrozen);
+        assert_ok!(LootNft::freeze_token(Origin::root(), 0, 0));
+        assert_eq!(LootNft::frozen_token(0, 0), Some(FrozenBy::FreezeOrigin));
+        assert_noop!(LootNft::freeze_token(Origin::signed(300), 0, 0), Error::<Test>::AlreadyFrozen);
+
+        // A frozen token can't move, be listed or be bought
+        assert_noop!(LootNft::nft_transfer(Origin::signed(100), 200, 0, 0), Error::<Test>::TokenIsFrozen);
+        assert_noop!(LootNft::batch_transfer(Origin::signed(100), vec![(200, 0, 0)]), Error::<Test>::TokenIsFrozen);
+        assert_noop!(LootNft::create_sale(Origin::signed(100), 0, 0, 30), Error::<Test>::TokenIsFrozen);
+        assert_noop!(LootNft::buy(Origin::signed(200), 0, 0), Error::<Test>::TokenIsFrozen);
+        assert_noop!(NFT::transfer(&100, &200, (0, 0)), Error::<Test>::TokenIsFrozen);
+        assert_noop!(LootNft::burn(Origin::signed(100), 0, 0), Error::<Test>::TokenIsFrozen);
+        assert_eq!(LootNft::listings(ListingFilter::default(), 0, 10), vec![]);
+
+        // Other tokens of the class are unaffected
+        assert_ok!(LootNft::nft_transfer(Origin::signed(100), 200, 0, 1));
+
+        // The class owner can't lift a freeze of the freeze origin
+        assert_noop!(LootNft::thaw_token(Origin::signed(300), 0, 0), Error::<Test>::InvalidPermission);
+        assert_ok!(LootNft::thaw_token(Origin::root(), 0, 0));
+        assert_ok!(LootNft::buy(Origin::signed(200), 0, 0));
+        assert_eq!(NFT::tokens(0, 0).unwrap().owner, TokenOwner::Account(200));
+
+        assert_eq!(loot_events()[4..7], [
+            RawEvent::TokenFrozen(0, 0),
+            RawEvent::TokenTransferred(100, 200, 0, 1),
+            RawEvent::TokenThawed(0, 0),
+        ]);
+    });
+}
+
+#[test]
+fn freeze_class() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LootNft::create_nft_class(Origin::signed(300), vec![0], (), 10, 10, false));
+        assert_ok!(LootNft::mint_nft_token(Origin::signed(100), 0, vec![0], ()));
+
+        assert_noop!(LootNft::freeze_class(Origin::signed(100), 0), Error::<Test>::InvalidPermission);
+        assert_noop!(LootNft::freeze_class(Origin::root(), 1), Error::<Test>::InvalidClassId);
+        assert_noop!(LootNft::thaw_class(Origin::signed(300), 0), Error::<Test>::NotFrozen);
+        assert_ok!(LootNft::freeze_class(Origin::signed(300), 0));
+        assert_noop!(LootNft::freeze_class(Origin::signed(300), 0), Error::<Test>::AlreadyFrozen);
+
+        assert_noop!(LootNft::nft_transfer(Origin::signed(100), 200, 0, 0), Error::<Test>::ClassIsFrozen);
+        assert_noop!(LootNft::create_sale(Origin::signed(100), 0, 0, 20), Error::<Test>::ClassIsFrozen);
+        assert_noop!(LootNft::burn(Origin::signed(100), 0, 0), Error::<Test>::ClassIsFrozen);
+
+        // The freeze origin can thaw a class frozen by its owner
+        assert_ok!(LootNft::thaw_class(Origin::root(), 0));
+        assert_ok!(LootNft::nft_transfer(Origin::signed(100), 200, 0, 0));
+
+        // and take over a freeze of the owner, who then can't lift it
+        assert_ok!(LootNft::freeze_class(Origin::signed(300), 0));
+        assert_ok!(LootNft::freeze_class(Origin::root(), 0));
+        assert_eq!(LootNft::frozen_class(0), Some(FrozenBy::FreezeOrigin));
+        assert_noop!(LootNft::thaw_class(Origin::signed(300), 0), Error::<Test>::InvalidPermission);
+
+        assert_eq!(loot_events()[2..], [
+            RawEvent::ClassFrozen(0),
+            RawEvent::ClassThawed(0),
+            RawEvent::TokenTransferred(100, 200, 0, 0),
+            RawEvent::ClassFrozen(0),
+            RawEvent::ClassFrozen(0),
+        ]);
+    });
+}
+
+#[test]
+fn nest_nft() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LootNft::create_nft_class(Origin::signed(300), vec![0], (), 10, 10, false));
+        assert_ok!(LootNft::mint_nft_token(Origin::signed(100), 0, vec![0], ()));
+        assert_ok!(LootNft::mint_nft_token(Origin::signed(100), 0, vec![0], ()));
+        assert_ok!(LootNft::create_sale(Origin::signed(100), 0, 1, 20));
+
+        // Only the owner can nest a token
+        assert_noop!(LootNft::nest_nft(Origin::signed(200), 0, 1, 0, 0), orml_nft::Error::<Test>::NoPermission);
+        assert_ok!(LootNft::nest_nft(Origin::signed(100), 0, 1, 0, 0));
+        assert_eq!(NFT::tokens(0, 1).unwrap().owner, TokenOwner::Token(0, 0));
+
+        // Nesting removes the listing even though the owner stays the same
+        assert_eq!(LootNft::sales(0, 1), None);
+
+        // The nested token moves with its parent
+        assert_ok!(LootNft::nft_transfer(Origin::signed(100), 200, 0, 0));
+        assert!(NFT::is_owner(&200, (0, 1)));
+
+        // A frozen nested token holds its parent back
+        assert_ok!(LootNft::freeze_token(Origin::root(), 0, 1));
+        assert_noop!(LootNft::nft_transfer(Origin::signed(200), 100, 0, 0), Error::<Test>::TokenIsFrozen);
+
+        assert_eq!(loot_events()[4..], [
+            RawEvent::TokenNested(100, 0, 1, 0, 0),
+            RawEvent::TokenTransferred(100, 200, 0, 0),
+            RawEvent::TokenFrozen(0, 1),
+        ]);
+    });
+}
+
+#[test]
+fn nest_nft_in_foreign_tokens() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LootNft::create_nft_class(Origin::signed(300), vec![0], (), 10, 10, false));
+        assert_ok!(LootNft::mint_nft_token(Origin::signed(100), 0, vec![0], ()));
+        assert_ok!(LootNft::mint_nft_token(Origin::signed(200), 0, vec![0], ()));
+        assert_ok!(LootNft::mint_nft_token(Origin::signed(100), 0, vec![0], ()));
+
+        // Nothing can be pushed into a token held by someone else
+        assert_noop!(LootNft::nest_nft(Origin::signed(100), 0, 0, 0, 1), orml_nft::Error::<Test>::NoPermission);
+
+        // Nor into a token held by the vault
+        assert_ok!(LootNft::fractionalize(Origin::signed(100), 0, 2, 10, 100));
+        assert_noop!(LootNft::nest_nft(Origin::signed(100), 0, 0, 0, 2), orml_nft::Error::<Test>::NoPermission);
+        assert!(NFT::is_owner(&100, (0, 0)));
+    });
+}
+
+#[test]
+fn fractionalize_and_redeem() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LootNft::create_nft_class(Origin::signed(300), vec![0], (), 10, 10, false));
+        assert_ok!(LootNft::mint_nft_token(Origin::signed(100), 0, vec![0], ()));
+        assert_ok!(LootNft::create_sale(Origin::signed(100), 0, 0, 20));
+
+        assert_noop!(LootNft::fractionalize(Origin::signed(200), 0, 0, 10, 100), Error::<Test>::TokenNotOwned);
+        assert_noop!(LootNft::fractionalize(Origin::signed(100), 0, 0, 0, 100), Error::<Test>::InvalidShares);
+        assert_ok!(LootNft::fractionalize(Origin::signed(100), 0, 0, 10, 100));
+        assert!(NFT::is_owner(&LootNft::vault_account(), (0, 0)));
+        assert_eq!(LootNft::sales(0, 0), None);
+        assert_eq!(LootNft::shares((0, 0), 100), 10);
+
+        assert_ok!(LootNft::transfer_shares(Origin::signed(100), 200, 0, 0, 4));
+        assert_noop!(LootNft::transfer_shares(Origin::signed(200), 300, 0, 0, 5), Error::<Test>::NotEnoughShares);
+        // at most two holders
+        assert_noop!(LootNft::transfer_shares(Origin::signed(200), 300, 0, 0, 1), Error::<Test>::TooManyShareHolders);
+        assert_eq!(LootNft::fractions(0, 0).map(|f| f.holders), Some(2));
+        // but a whole balance can go to a new holder
+        assert_ok!(LootNft::transfer_shares(Origin::signed(200), 300, 0, 0, 4));
+        assert_eq!(LootNft::fractions(0, 0).map(|f| f.holders), Some(2));
+        assert_eq!(LootNft::shares((0, 0), 200), 0);
+        assert_eq!(LootNft::shares((0, 0), 300), 4);
+
+        // all shares are needed to redeem
+        assert_noop!(LootNft::redeem(Origin::signed(100), 0, 0), Error::<Test>::NotEnoughShares);
+        assert_ok!(LootNft::transfer_shares(Origin::signed(300), 100, 0, 0, 4));
+        assert_eq!(LootNft::fractions(0, 0).map(|f| f.holders), Some(1));
+        assert_ok!(LootNft::redeem(Origin::signed(100), 0, 0));
+        assert_eq!(NFT::tokens(0, 0).unwrap().owner, TokenOwner::Account(100));
+        assert_eq!(LootNft::fractions(0, 0), None);
+        assert_eq!(LootNft::shares((0, 0), 100), 0);
+
+        assert_eq!(loot_events()[3..], [
+            RawEvent::TokenFractionalized(100, 0, 0, 10, 100),
+            RawEvent::SharesTransferred(100, 200, 0, 0, 4),
+            RawEvent::SharesTransferred(200, 300, 0, 0, 4),
+            RawEvent::SharesTransferred(300, 100, 0, 0, 4),
+            RawEvent::TokenRedeemed(100, 0, 0),
+        ]);
+    });
+}
+
+#[test]
+fn buyout() {
+    new_test_ext().execute_with(|| {
+        assert_ok!(LootNft::create_nft_class(Origin::signed(300), vec![0], (), 10, 10, false));
+        assert_ok!(LootNft::mint_nft_token(Origin::signed(100), 0, vec![0], ()));
+        assert_ok!(LootNft::fractionalize(Origin::signed(100), 0, 0, 10, 100));
+        assert_ok!(LootNft::transfer_shares(Origin::signed(100), 200, 0, 0, 4));
+
+        assert_noop!(LootNft::buyout(Origin::signed(200), 0, 1), Error::<Test>::TokenNotFractionalized);
+        assert_ok!(LootNft::buyout(Origin::signed(200), 0, 0));
+
+        // 10 royalty to the class owner, 90 split pro rata; the buyer keeps its own share
+        assert_eq!(Balances::free_balance(100), 490 + 54);
+        assert_eq!(Balances::free_balance(200), 500 - 10 - 54);
+        assert_eq!(Balances::free_balance(300), 510 + 10);
+
+        assert_eq!(NFT::tokens(0, 0).unwrap().owner, TokenOwner::Account(200));
+        assert_eq!(LootNft::fractions(0, 0), None);
+        assert_eq!(LootNft::shares((0, 0), 100), 0);
+        assert_eq!(LootNft::shares((0, 0), 200), 0);
+
+        assert_eq!(loot_events()[4..], [
+            RawEvent::RoyaltySent(200, 300, 0, 10),
+            RawEvent::TokenBoughtOut(200, 0, 0, 100, 10),
+        ]);
+    });
+}
+
+#[test]
+fn buyout_dust_payouts() {
+    new_test_ext().execute_with(|| {
+        ExistentialDeposit::set(10);
+        assert_ok!(LootNft::create_nft_class(Origin::signed(300), vec![0], (), 10, 10, false));
+        assert_ok!(LootNft::mint_nft_token(Origin::signed(100), 0, vec![0], ()));
+        assert_ok!(LootNft::fractionalize(Origin::signed(100), 0, 0, 100, 400));
+        // 400 has no funds, and its cut of 3 can't open its account
+        assert_ok!(LootNft::transfer_shares(Origin::signed(100), 400, 0, 0, 1));
+
+        let issuance = Balances::total_issuance();
+        assert_ok!(LootNft::buyout(Origin::signed(200), 0, 0));
+
+        // 40 royalty, 360 split pro rata, with the dust handed to `OnPlatformFee`
+        assert_eq!(Balances::free_balance(100), 490 + 356);
+        assert_eq!(Balances::free_balance(200), 500 - 40 - 356 - 3);
+        assert_eq!(Balances::free_balance(300), 510 + 40);
+        assert_eq!(Balances::free_balance(400), 0);
+        assert_eq!(Balances::total_issuance(), issuance - 3);
+        assert_eq!(NFT::tokens(0, 0).unwrap().owner, TokenOwner::Account(200));
+    });
+}
+
+#[test]
+fn platform_fee() {
+    new_test_ext().execute_with(|| {
+        PlatformFee::set(Permill::from_percent(5));
+        assert_ok!(LootNft::create_nft_class(Origin::signed(300), vec![0], (), 10, 10, false));
+        assert_ok!(LootNft::mint_nft_token(Origin::signed(100), 0, vec![0], ()));
+        assert_ok!(LootNft::create_sale(Origin::signed(100), 0, 0, 100));
+
+        assert_eq!(LootNft::quote_sale(0, 0, None), Ok(SaleQuote {
+            seller: 100,
+            royalty_recipient: 300,
+            price: 100,
+            royalty: 10,
+            platform_fee: 5,
+            seller_receives: 85,
+        }));
+
+        let issuance = Balances::total_issuance();
+        assert_ok!(LootNft::buy(Origin::signed(200), 0, 0));
+
+        assert_eq!(Balances::free_balance(100), 490 + 85);
+        assert_eq!(Balances::free_balance(200), 500 - 100);
+        assert_eq!(Balances::free_balance(300), 510 + 10);
+        // the mock burns platform fees
+        assert_eq!(Balances::total_issuance(), issuance - 5);
+
+        assert_eq!(loot_events()[3..], [
+            RawEvent::RoyaltySent(200, 300, 0, 10),
+            RawEvent::PlatformFeePaid(200, 0, 5),
+            RawEvent::TokenSaleCompleted(100, 200, 0, 0, 100, 10),
+        ]);
+    });
+}
+
+#[test]
+fn failed_buy_is_reverted() {
+    new_test_ext().execute_with(|| {
+        PlatformFee::set(Permill::from_percent(5));
+        assert_ok!(LootNft::create_nft_class(Origin::signed(300), vec![0], (), 10, 10, false));
+        assert_ok!(LootNft::mint_nft_token(Origin::signed(100), 0, vec![0], ()));
+        assert_ok!(LootNft::create_sale(Origin::signed(100), 0, 0, 100));
+
+        // reserved funds count towards the quote, but can't pay the platform fee left for last
+        assert_ok!(Balances::reserve(&200, 403));
+        assert_noop!(
+            LootNft::buy(Origin::signed(200), 0, 0),
+            pallet_balances::Error::<Test, pallet_balances::DefaultInstance>::InsufficientBalance
+        );
+        assert_eq!(Balances::free_balance(100), 490);
+        assert_eq!(NFT::tokens(0, 0).unwrap().owner, TokenOwner::Account(100));
+    });
+}
+
+#[test]
+fn genesis_classes() {
+    let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+    let token = |owner, sale| GenesisToken { owner, metadata: vec![1], data: (), sale };
+    GenesisConfig::<Test> {
+        classes: vec![
+            GenesisClass {
+                owner: 100,
+                metadata: vec![0],
+                data: (),
+                mint_price: 10,
+                royalty: 5,
+                soulbound: false,
+                tokens: vec![token(100, Some(50)), token(200, None)],
+            },
+            GenesisClass {
+                owner: 200,
+                metadata: vec![0],
+                data: (),
+                mint_price: 0,
+                royalty: 0,
+                soulbound: true,
+                tokens: vec![token(300, None)],
+            },
+        ],
+    }
+    .assimilate_storage(&mut t)
+    .unwrap();
+
+    sp_io::TestExternalities::from(t).execute_with(|| {
+        assert_eq!(NFT::next_class_id(), 2);
+        assert_eq!(LootNft::info(0), Some((true, 10, 5)));
+        assert_eq!(LootNft::info(1), Some((true, 0, 0)));
+        assert!(!LootNft::soulbound(0));
+        assert!(LootNft::soulbound(1));
+
+        assert!(NFT::is_owner(&100, (0, 0)));
+        assert!(NFT::is_owner(&200, (0, 1)));
+        assert!(NFT::is_owner(&300, (1, 0)));
+        assert_eq!(LootNft::sales(0, 0), Some(50));
+        assert_eq!(LootNft::sales(0, 1), None);
+    });
+}
@@ -0,0 +1,26 @@
+//! Retiring sudo once the council, technical committee and democracy have taken over.
+
+use frame_support::{
+    storage::unhashed,
+    traits::{Get, OnRuntimeUpgrade},
+    weights::Weight,
+    StorageHasher, Twox128,
+};
+use sp_std::prelude::*;
+
+/// Remove the sudo key left in storage once `Sudo` is dropped from `construct_runtime!`.
+///
+/// To hand the chain over to governance, ship a runtime upgrade that removes `Sudo` from
+/// `construct_runtime!`, its `impl pallet_sudo::Trait` and the `pallet-sudo` dependency, and adds
+/// this to the migrations of `Executive`. Root is then only reachable through referenda, and the
+/// council keeps its majority origins. Take it out again in the following upgrade.
+pub struct RemoveSudo;
+
+impl OnRuntimeUpgrade for RemoveSudo {
+    fn on_runtime_upgrade() -> Weight {
+        let key = [Twox128::hash(b"Sudo"), Twox128::hash(b"Key")].concat();
+        unhashed::kill(&key);
+
+        <crate::Runtime as frame_system::Trait>::DbWeight::get().writes(1)
+    }
+}
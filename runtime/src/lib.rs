@@ -8,16 +8,21 @@ include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
 use pallet_grandpa::fg_primitives;
 use pallet_grandpa::{AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList};
+use pallet_session::historical as pallet_session_historical;
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
-use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
+use sp_core::{
+    crypto::KeyTypeId,
+    u32_trait::{_1, _2, _3, _4, _5},
+    OpaqueMetadata,
+};
 use sp_runtime::traits::{
-    BlakeTwo256, Block as BlockT, IdentifyAccount, IdentityLookup, NumberFor, Saturating, Verify,
+    BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, OpaqueKeys, Saturating, Verify,
 };
 use sp_runtime::{
     create_runtime_str, generic, impl_opaque_keys,
-    transaction_validity::{TransactionSource, TransactionValidity},
-    ApplyExtrinsicResult, MultiSignature,
+    transaction_validity::{TransactionPriority, TransactionSource, TransactionValidity},
+    ApplyExtrinsicResult, ModuleId, MultiSignature, Percent,
 };
 use sp_std::prelude::*;
 #[cfg(feature = "std")]
@@ -35,6 +40,7 @@ pub use frame_support::{
     StorageValue,
 };
 pub use pallet_balances::Call as BalancesCall;
+pub use pallet_staking::StakerStatus;
 pub use pallet_timestamp::Call as TimestampCall;
 #[cfg(any(feature = "std", test))]
 pub use sp_runtime::BuildStorage;
@@ -42,6 +48,19 @@ pub use sp_runtime::{Perbill, Permill};
 
 pub use orml_nft;
 
+pub mod governance;
+pub mod lookup;
+pub mod nft;
+pub mod pause;
+pub mod proxy;
+pub mod staking;
+pub mod treasury;
+#[cfg(feature = "std")]
+pub mod type_definitions;
+
+pub use lookup::MultiAddress;
+pub use nft::{ClassData, Rarity, TokenData};
+
 use frame_system::{EnsureOneOf, EnsureRoot};
 
 pub use pallet_identity;
@@ -67,6 +86,9 @@ pub const MINUTES: BlockNumber = 60_000 / (MILLISECS_PER_BLOCK as BlockNumber);
 pub const HOURS: BlockNumber = MINUTES * 60;
 pub const DAYS: BlockNumber = HOURS * 24;
 
+/// Validators and their session keys change from one session to the next.
+pub const SESSION_DURATION: BlockNumber = 1 * HOURS;
+
 /// An index to a block.
 pub type BlockNumber = u32;
 
@@ -93,6 +115,12 @@ pub type Hash = sp_core::H256;
 /// Digest item type.
 pub type DigestItem = generic::DigestItem<Hash>;
 
+/// Identifier of an NFT class.
+pub type ClassId = u64;
+
+/// Identifier of an NFT within its class.
+pub type TokenId = u64;
+
 /// Opaque types. These are used by the CLI to instantiate machinery that don't need to know
 /// the specifics of the runtime. They can then be made to be agnostic over specific formats
 /// of data like extrinsics, allowing for them to continue syncing the network through upgrades
@@ -121,10 +149,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     spec_name: create_runtime_str!("loot"),
     impl_name: create_runtime_str!("loot"),
     authoring_version: 1,
-    spec_version: 20,
+    spec_version: 55,
     impl_version: 10,
     apis: RUNTIME_API_VERSIONS,
-    transaction_version: 1,
+    transaction_version: 3,
 };
 
 // pub const MILLISECS_PER_BLOCK: u64 = 6000;
@@ -145,10 +173,28 @@ pub fn native_version() -> NativeVersion {
     }
 }
 
-type SelfOrSudo = EnsureOneOf<
+type CouncilCollective = pallet_collective::Instance1;
+type TechnicalCollective = pallet_collective::Instance2;
+
+/// Root, i.e. sudo or a referendum, or more than half of the council.
+type RootOrCouncilMajority = EnsureOneOf<
     AccountId,
     EnsureRoot<AccountId>,
-    EnsureRoot<AccountId>, // FIXME: deduplicate - possibly let registrars make modifications?
+    pallet_collective::EnsureProportionMoreThan<_1, _2, AccountId, CouncilCollective>,
+>;
+
+/// Root, or at least three fifths of the council.
+type RootOrCouncilSupermajority = EnsureOneOf<
+    AccountId,
+    EnsureRoot<AccountId>,
+    pallet_collective::EnsureProportionAtLeast<_3, _5, AccountId, CouncilCollective>,
+>;
+
+/// Root or any identity registrar, e.g. to freeze stolen items while a dispute is investigated.
+type RootOrRegistrar = EnsureOneOf<
+    AccountId,
+    EnsureRoot<AccountId>,
+    pallet_identity::EnsureRegistrar<Runtime>,
 >;
 
 parameter_types! {
@@ -166,14 +212,14 @@ parameter_types! {
 // Configure FRAME pallets to include in runtime.
 
 impl frame_system::Trait for Runtime {
-    /// The basic call filter to use in dispatchable.
-    type BaseCallFilter = ();
+    /// The basic call filter to use in dispatchable. Root dispatches bypass it.
+    type BaseCallFilter = Pause;
     /// The identifier used to distinguish between accounts.
     type AccountId = AccountId;
     /// The aggregated dispatch type that is available for extrinsics.
     type Call = Call;
     /// The lookup mechanism to get account ID from whatever is passed in dispatchers.
-    type Lookup = IdentityLookup<AccountId>;
+    type Lookup = lookup::AccountLookup;
     /// The index type for storing how many extrinsics an account has signed.
     type Index = Index;
     /// The index type for blocks.
@@ -232,7 +278,8 @@ impl pallet_grandpa::Trait for Runtime {
     type Event = Event;
     type Call = Call;
 
-    type KeyOwnerProofSystem = ();
+    /// Proves a GRANDPA key belonged to a validator in the session it equivocated in.
+    type KeyOwnerProofSystem = Historical;
 
     type KeyOwnerProof =
         <Self::KeyOwnerProofSystem as KeyOwnerProofSystem<(KeyTypeId, GrandpaId)>>::Proof;
@@ -242,7 +289,9 @@ impl pallet_grandpa::Trait for Runtime {
         GrandpaId,
     )>>::IdentificationTuple;
 
-    type HandleEquivocation = ();
+    /// Reports equivocations to `pallet_offences`, which has staking slash the offender.
+    type HandleEquivocation =
+        pallet_grandpa::EquivocationHandler<Self::KeyOwnerIdentification, Offences>;
 
     type WeightInfo = ();
 }
@@ -276,13 +325,25 @@ impl pallet_balances::Trait for Runtime {
     type WeightInfo = ();
 }
 
+parameter_types! {
+    pub const IndexDeposit: Balance = 1 * DOLLARS;
+}
+
+impl pallet_indices::Trait for Runtime {
+    type AccountIndex = AccountIndex;
+    type Currency = Balances;
+    type Deposit = IndexDeposit;
+    type Event = Event;
+    type WeightInfo = ();
+}
+
 parameter_types! {
     pub const TransactionByteFee: Balance = 1;
 }
 
 impl pallet_transaction_payment::Trait for Runtime {
     type Currency = Balances;
-    type OnTransactionPayment = ();
+    type OnTransactionPayment = treasury::DealWithFees;
     type TransactionByteFee = TransactionByteFee;
     type WeightToFee = IdentityFee<Balance>;
     type FeeMultiplierUpdate = ();
@@ -293,20 +354,39 @@ impl pallet_sudo::Trait for Runtime {
     type Call = Call;
 }
 
+parameter_types! {
+    pub const MaxNestingDepth: u32 = 5;
+}
+
 impl orml_nft::Trait for Runtime {
-    type ClassId = u64;
-    type TokenId = u64;
-    type ClassData = u32;
-    type TokenData = u32;
+    type ClassId = ClassId;
+    type TokenId = TokenId;
+    type ClassData = ClassData;
+    type TokenData = TokenData;
+    type MaxNestingDepth = MaxNestingDepth;
+    type OnTransfer = LootNFT;
+    type OnMint = ();
+    type OnBurn = LootNFT;
 }
 
 parameter_types! {
-    pub const LootRoyaltyFee: u128 = 10;
+    pub const LootPlatformFee: Permill = Permill::from_percent(1);
+    pub const MaxBatchSize: u32 = 100;
+    pub const LootModuleId: ModuleId = ModuleId(*b"loot/nft");
+    pub const MaxShareHolders: u32 = 100;
 }
 
 impl royalty_nft::Trait for Runtime {
     type Event = Event;
     type Currency = Balances;
+    type Nft = OrmlNFT;
+    type MaxBatchSize = MaxBatchSize;
+    type FreezeOrigin = RootOrRegistrar;
+    type ModuleId = LootModuleId;
+    type MaxShareHolders = MaxShareHolders;
+    type DataPolicy = nft::LootDataPolicy;
+    type PlatformFee = LootPlatformFee;
+    type OnPlatformFee = Treasury;
 }
 
 parameter_types! {
@@ -330,12 +410,320 @@ impl pallet_identity::Trait for Runtime {
     type MaxSubAccounts = MaxSubAccounts;
     type MaxAdditionalFields = MaxAdditionalFields;
     type MaxRegistrars = MaxRegistrars;
-    type Slashed = ();
-    type ForceOrigin = SelfOrSudo; // FIXME: needs to be set properly for production!
-    type RegistrarOrigin = SelfOrSudo; // FIXME: needs to be set properly for production!
+    type Slashed = Treasury;
+    type ForceOrigin = RootOrCouncilMajority;
+    type RegistrarOrigin = RootOrCouncilMajority;
     type WeightInfo = (); // FIXME: use real weights -- weights::pallet_identity::WeightInfo<Runtime>;
 }
 
+parameter_types! {
+    pub MaximumSchedulerWeight: Weight = Perbill::from_percent(80) * MaximumBlockWeight::get();
+    pub const MaxScheduledPerBlock: u32 = 50;
+}
+
+impl pallet_scheduler::Trait for Runtime {
+    type Event = Event;
+    type Origin = Origin;
+    type PalletsOrigin = OriginCaller;
+    type Call = Call;
+    type MaximumWeight = MaximumSchedulerWeight;
+    type ScheduleOrigin = EnsureRoot<AccountId>;
+    type MaxScheduledPerBlock = MaxScheduledPerBlock;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const CouncilMotionDuration: BlockNumber = 3 * DAYS;
+    pub const CouncilMaxProposals: u32 = 100;
+    pub const CouncilMaxMembers: u32 = 100;
+}
+
+impl pallet_collective::Trait<CouncilCollective> for Runtime {
+    type Origin = Origin;
+    type Proposal = Call;
+    type Event = Event;
+    type MotionDuration = CouncilMotionDuration;
+    type MaxProposals = CouncilMaxProposals;
+    type MaxMembers = CouncilMaxMembers;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const TechnicalMotionDuration: BlockNumber = 3 * DAYS;
+    pub const TechnicalMaxProposals: u32 = 100;
+    pub const TechnicalMaxMembers: u32 = 100;
+}
+
+impl pallet_collective::Trait<TechnicalCollective> for Runtime {
+    type Origin = Origin;
+    type Proposal = Call;
+    type Event = Event;
+    type MotionDuration = TechnicalMotionDuration;
+    type MaxProposals = TechnicalMaxProposals;
+    type MaxMembers = TechnicalMaxMembers;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const LaunchPeriod: BlockNumber = 7 * DAYS;
+    pub const VotingPeriod: BlockNumber = 7 * DAYS;
+    pub const FastTrackVotingPeriod: BlockNumber = 3 * HOURS;
+    pub const InstantAllowed: bool = true;
+    pub const MinimumDeposit: Balance = 100 * DOLLARS;
+    pub const EnactmentPeriod: BlockNumber = 2 * DAYS;
+    pub const CooloffPeriod: BlockNumber = 7 * DAYS;
+    pub const PreimageByteDeposit: Balance = 1 * CENTS;
+    pub const MaxVotes: u32 = 100;
+}
+
+impl pallet_democracy::Trait for Runtime {
+    type Proposal = Call;
+    type Event = Event;
+    type Currency = Balances;
+    type EnactmentPeriod = EnactmentPeriod;
+    type LaunchPeriod = LaunchPeriod;
+    type VotingPeriod = VotingPeriod;
+    type MinimumDeposit = MinimumDeposit;
+    /// A majority of the council can choose the next external referendum.
+    type ExternalOrigin =
+        pallet_collective::EnsureProportionAtLeast<_1, _2, AccountId, CouncilCollective>;
+    /// Three quarters of the council can make it a simple majority vote.
+    type ExternalMajorityOrigin =
+        pallet_collective::EnsureProportionAtLeast<_3, _4, AccountId, CouncilCollective>;
+    /// A unanimous council can make it pass unless a majority votes against it.
+    type ExternalDefaultOrigin =
+        pallet_collective::EnsureProportionAtLeast<_1, _1, AccountId, CouncilCollective>;
+    /// Two thirds of the technical committee can fast track an external referendum...
+    type FastTrackOrigin =
+        pallet_collective::EnsureProportionAtLeast<_2, _3, AccountId, TechnicalCollective>;
+    /// ...and all of it can start it with any voting period, e.g. for an urgent fix.
+    type InstantOrigin =
+        pallet_collective::EnsureProportionAtLeast<_1, _1, AccountId, TechnicalCollective>;
+    type InstantAllowed = InstantAllowed;
+    type FastTrackVotingPeriod = FastTrackVotingPeriod;
+    /// Two thirds of the council can cancel a referendum that is underway.
+    type CancellationOrigin =
+        pallet_collective::EnsureProportionAtLeast<_2, _3, AccountId, CouncilCollective>;
+    /// Any member of the technical committee can veto an external proposal once, for the
+    /// cooloff period.
+    type VetoOrigin = pallet_collective::EnsureMember<AccountId, TechnicalCollective>;
+    type CooloffPeriod = CooloffPeriod;
+    type PreimageByteDeposit = PreimageByteDeposit;
+    type OperationalPreimageOrigin = pallet_collective::EnsureMember<AccountId, CouncilCollective>;
+    type Slash = Treasury;
+    type Scheduler = Scheduler;
+    type PalletsOrigin = OriginCaller;
+    type MaxVotes = MaxVotes;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const TreasuryModuleId: ModuleId = ModuleId(*b"py/trsry");
+    pub const ProposalBond: Permill = Permill::from_percent(5);
+    pub const ProposalBondMinimum: Balance = 1 * DOLLARS;
+    pub const SpendPeriod: BlockNumber = 1 * DAYS;
+    pub const Burn: Permill = Permill::from_percent(0);
+    pub const TipCountdown: BlockNumber = 1 * DAYS;
+    pub const TipFindersFee: Percent = Percent::from_percent(20);
+    pub const TipReportDepositBase: Balance = 1 * DOLLARS;
+    pub const DataDepositPerByte: Balance = 1 * CENTS;
+    pub const BountyDepositBase: Balance = 1 * DOLLARS;
+    pub const BountyDepositPayoutDelay: BlockNumber = 1 * DAYS;
+    pub const BountyUpdatePeriod: BlockNumber = 14 * DAYS;
+    pub const BountyCuratorDeposit: Permill = Permill::from_percent(50);
+    pub const BountyValueMinimum: Balance = 5 * DOLLARS;
+    pub const MaximumReasonLength: u32 = 16384;
+}
+
+/// Funded by transaction fees, marketplace platform fees and identity and democracy slashes.
+impl pallet_treasury::Trait for Runtime {
+    type ModuleId = TreasuryModuleId;
+    type Currency = Balances;
+    type ApproveOrigin = RootOrCouncilSupermajority;
+    type RejectOrigin = RootOrCouncilMajority;
+    type Tippers = treasury::CouncilTippers;
+    type TipCountdown = TipCountdown;
+    type TipFindersFee = TipFindersFee;
+    type TipReportDepositBase = TipReportDepositBase;
+    type DataDepositPerByte = DataDepositPerByte;
+    type Event = Event;
+    type OnSlash = ();
+    type ProposalBond = ProposalBond;
+    type ProposalBondMinimum = ProposalBondMinimum;
+    type SpendPeriod = SpendPeriod;
+    type Burn = Burn;
+    type BountyDepositBase = BountyDepositBase;
+    type BountyDepositPayoutDelay = BountyDepositPayoutDelay;
+    type BountyUpdatePeriod = BountyUpdatePeriod;
+    type BountyCuratorDeposit = BountyCuratorDeposit;
+    type BountyValueMinimum = BountyValueMinimum;
+    type MaximumReasonLength = MaximumReasonLength;
+    type BurnDestination = ();
+    type WeightInfo = ();
+}
+
+impl pallet_utility::Trait for Runtime {
+    type Event = Event;
+    type Call = Call;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    // One storage item; key size 32, value size 8.
+    pub const ProxyDepositBase: Balance = deposit(1, 8);
+    // Additional storage item size of 33 bytes.
+    pub const ProxyDepositFactor: Balance = deposit(0, 33);
+    pub const MaxProxies: u16 = 32;
+    pub const AnnouncementDepositBase: Balance = deposit(1, 8);
+    pub const AnnouncementDepositFactor: Balance = deposit(0, 66);
+    pub const MaxPending: u16 = 32;
+}
+
+impl pallet_proxy::Trait for Runtime {
+    type Event = Event;
+    type Call = Call;
+    type Currency = Balances;
+    type ProxyType = proxy::ProxyType;
+    type ProxyDepositBase = ProxyDepositBase;
+    type ProxyDepositFactor = ProxyDepositFactor;
+    type MaxProxies = MaxProxies;
+    type WeightInfo = ();
+    type MaxPending = MaxPending;
+    type CallHasher = BlakeTwo256;
+    type AnnouncementDepositBase = AnnouncementDepositBase;
+    type AnnouncementDepositFactor = AnnouncementDepositFactor;
+}
+
+parameter_types! {
+    // One storage item; key size is 32; value is size 4+4+16+32 bytes = 56 bytes.
+    pub const MultisigDepositBase: Balance = deposit(1, 88);
+    // Additional storage item size of 32 bytes.
+    pub const MultisigDepositFactor: Balance = deposit(0, 32);
+    pub const MaxSignatories: u16 = 100;
+}
+
+impl pallet_multisig::Trait for Runtime {
+    type Event = Event;
+    type Call = Call;
+    type Currency = Balances;
+    type DepositBase = MultisigDepositBase;
+    type DepositFactor = MultisigDepositFactor;
+    type MaxSignatories = MaxSignatories;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const MaxPauseNameLength: u32 = 64;
+}
+
+impl pallet_pause::Trait for Runtime {
+    type Event = Event;
+    type Call = Call;
+    type PauseOrigin = RootOrCouncilMajority;
+    type UnpausablePallets = pause::UnpausablePallets;
+    type MaxNameLength = MaxPauseNameLength;
+}
+
+parameter_types! {
+    pub const SessionPeriod: BlockNumber = SESSION_DURATION;
+    pub const SessionOffset: BlockNumber = 0;
+    pub const DisabledValidatorsThreshold: Perbill = Perbill::from_percent(17);
+}
+
+/// Rotates the Aura and GRANDPA authorities to the validators elected by `pallet_staking`.
+impl pallet_session::Trait for Runtime {
+    type Event = Event;
+    type ValidatorId = AccountId;
+    type ValidatorIdOf = pallet_staking::StashOf<Self>;
+    type ShouldEndSession = pallet_session::PeriodicSessions<SessionPeriod, SessionOffset>;
+    type NextSessionRotation = pallet_session::PeriodicSessions<SessionPeriod, SessionOffset>;
+    type SessionManager = pallet_session::historical::NoteHistoricalRoot<Self, Staking>;
+    type SessionHandler = <opaque::SessionKeys as OpaqueKeys>::KeyTypeIdProviders;
+    type Keys = opaque::SessionKeys;
+    type DisabledValidatorsThreshold = DisabledValidatorsThreshold;
+    type WeightInfo = ();
+}
+
+impl pallet_session::historical::Trait for Runtime {
+    type FullIdentification = pallet_staking::Exposure<AccountId, Balance>;
+    type FullIdentificationOf = pallet_staking::ExposureOf<Runtime>;
+}
+
+parameter_types! {
+    /// Aura has no uncles.
+    pub const UncleGenerations: BlockNumber = 0;
+}
+
+/// Credits block authors with era points, which staking rewards are paid by.
+impl pallet_authorship::Trait for Runtime {
+    type FindAuthor = pallet_session::FindAccountFromAuthorIndex<Self, Aura>;
+    type UncleGenerations = UncleGenerations;
+    type FilterUncle = ();
+    type EventHandler = Staking;
+}
+
+parameter_types! {
+    pub const SessionsPerEra: u32 = 6;
+    pub const BondingDuration: pallet_staking::EraIndex = 4 * 7;
+    /// A quarter of the bonding duration, for the council to cancel unjust slashes.
+    pub const SlashDeferDuration: pallet_staking::EraIndex = 7;
+    pub const ElectionLookahead: BlockNumber = SESSION_DURATION / 4;
+    pub const MaxNominatorRewardedPerValidator: u32 = 64;
+    pub const MaxIterations: u32 = 10;
+    /// 0.05%. The higher the value, the more strict solution acceptance becomes.
+    pub MinSolutionScoreBump: Perbill = Perbill::from_rational_approximation(5u32, 10_000);
+    pub const StakingUnsignedPriority: TransactionPriority = TransactionPriority::max_value() / 2;
+    pub OffchainSolutionWeightLimit: Weight = MaximumExtrinsicWeight::get()
+        .saturating_sub(BlockExecutionWeight::get())
+        .saturating_sub(ExtrinsicBaseWeight::get());
+}
+
+/// Nominated proof-of-stake: eras of six sessions, validators elected by stake at the end of
+/// each era, and rewards paid per era from the reward curve.
+impl pallet_staking::Trait for Runtime {
+    type Currency = Balances;
+    type UnixTime = Timestamp;
+    type CurrencyToVote = staking::CurrencyToVoteHandler;
+    type RewardRemainder = Treasury;
+    type Event = Event;
+    type Slash = Treasury;
+    type Reward = ();
+    type SessionsPerEra = SessionsPerEra;
+    type BondingDuration = BondingDuration;
+    type SlashDeferDuration = SlashDeferDuration;
+    type SlashCancelOrigin = RootOrCouncilSupermajority;
+    type SessionInterface = Self;
+    type RewardCurve = staking::RewardCurve;
+    type NextNewSession = Session;
+    type ElectionLookahead = ElectionLookahead;
+    type Call = Call;
+    type MaxIterations = MaxIterations;
+    type MinSolutionScoreBump = MinSolutionScoreBump;
+    type MaxNominatorRewardedPerValidator = MaxNominatorRewardedPerValidator;
+    type UnsignedPriority = StakingUnsignedPriority;
+    type OffchainSolutionWeightLimit = OffchainSolutionWeightLimit;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub OffencesWeightSoftLimit: Weight = Perbill::from_percent(60) * MaximumBlockWeight::get();
+}
+
+impl pallet_offences::Trait for Runtime {
+    type Event = Event;
+    type IdentificationTuple = pallet_session::historical::IdentificationTuple<Self>;
+    type OnOffenceHandler = Staking;
+    type WeightSoftLimit = OffencesWeightSoftLimit;
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
+where
+    Call: From<C>,
+{
+    type Extrinsic = UncheckedExtrinsic;
+    type OverarchingCall = Call;
+}
+
 // Create the runtime by composing the FRAME pallets that were previously configured.
 construct_runtime!(
     pub enum Runtime where
@@ -347,18 +735,35 @@ construct_runtime!(
         RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Module, Call, Storage},
         Timestamp: pallet_timestamp::{Module, Call, Storage, Inherent},
         Aura: pallet_aura::{Module, Config<T>, Inherent},
-        Grandpa: pallet_grandpa::{Module, Call, Storage, Config, Event},
+        Grandpa: pallet_grandpa::{Module, Call, Storage, Config, Event, ValidateUnsigned},
         Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
         TransactionPayment: pallet_transaction_payment::{Module, Storage},
+        // Remove once governance has taken over, see `governance::RemoveSudo`.
         Sudo: pallet_sudo::{Module, Call, Config<T>, Storage, Event<T>},
-        LootNFT: royalty_nft::{Module, Call, Storage, Event<T>},
-        OrmlNFT: orml_nft::{Module, Storage},
+        LootNFT: royalty_nft::{Module, Call, Storage, Event<T>, Config<T>},
+        OrmlNFT: orml_nft::{Module, Storage, Config<T>},
         Identity: pallet_identity::{Module, Call, Storage, Event<T>},
+        Scheduler: pallet_scheduler::{Module, Call, Storage, Event<T>},
+        Democracy: pallet_democracy::{Module, Call, Storage, Config, Event<T>},
+        Council: pallet_collective::<Instance1>::{Module, Call, Storage, Origin<T>, Event<T>, Config<T>},
+        TechnicalCommittee: pallet_collective::<Instance2>::{Module, Call, Storage, Origin<T>, Event<T>, Config<T>},
+        Treasury: pallet_treasury::{Module, Call, Storage, Config, Event<T>},
+        Indices: pallet_indices::{Module, Call, Storage, Config<T>, Event<T>},
+        Utility: pallet_utility::{Module, Call, Event},
+        Proxy: pallet_proxy::{Module, Call, Storage, Event<T>},
+        Multisig: pallet_multisig::{Module, Call, Storage, Event<T>},
+        Pause: pallet_pause::{Module, Call, Storage, Event},
+        Authorship: pallet_authorship::{Module, Storage},
+        // before `Session`, whose genesis asks staking for the first validators
+        Staking: pallet_staking::{Module, Call, Config<T>, Storage, Event<T>, ValidateUnsigned},
+        Session: pallet_session::{Module, Call, Storage, Event, Config<T>},
+        Historical: pallet_session_historical::{Module},
+        Offences: pallet_offences::{Module, Call, Storage, Event},
     }
 );
 
 /// The address format for describing accounts.
-pub type Address = AccountId;
+pub type Address = MultiAddress<AccountId, AccountIndex>;
 /// Block header type as expected by this runtime.
 pub type Header = generic::Header<BlockNumber, BlakeTwo256>;
 /// Block type as expected by this runtime.
@@ -388,6 +793,7 @@ pub type Executive = frame_executive::Executive<
     frame_system::ChainContext<Runtime>,
     Runtime,
     AllModules,
+    (nft::MigrateNftData, staking::SeedValidators),
 >;
 
 impl_runtime_apis! {
@@ -479,23 +885,29 @@ impl_runtime_apis! {
         }
 
         fn submit_report_equivocation_unsigned_extrinsic(
-            _equivocation_proof: fg_primitives::EquivocationProof<
+            equivocation_proof: fg_primitives::EquivocationProof<
                 <Block as BlockT>::Hash,
                 NumberFor<Block>,
             >,
-            _key_owner_proof: fg_primitives::OpaqueKeyOwnershipProof,
+            key_owner_proof: fg_primitives::OpaqueKeyOwnershipProof,
         ) -> Option<()> {
-            None
+            let key_owner_proof = key_owner_proof.decode()?;
+
+            Grandpa::submit_unsigned_equivocation_report(
+                equivocation_proof,
+                key_owner_proof,
+            )
         }
 
         fn generate_key_ownership_proof(
             _set_id: fg_primitives::SetId,
-            _authority_id: GrandpaId,
+            authority_id: GrandpaId,
         ) -> Option<fg_primitives::OpaqueKeyOwnershipProof> {
-            // NOTE: this is the only implementation possible since we've
-            // defined our key owner proof type as a bottom type (i.e. a type
-            // with no values).
-            None
+            use codec::Encode;
+
+            Historical::prove((fg_primitives::KEY_TYPE, authority_id))
+                .map(|p| p.encode())
+                .map(fg_primitives::OpaqueKeyOwnershipProof::new)
         }
     }
 
@@ -514,6 +926,47 @@ impl_runtime_apis! {
         }
     }
 
+    impl royalty_nft_rpc_runtime_api::RoyaltyApi<Block, AccountId, ClassId, TokenId, Balance> for Runtime {
+        fn class_info(class_id: ClassId) -> Option<royalty_nft::ClassDetails<AccountId, Balance>> {
+            LootNFT::class_info(class_id)
+        }
+
+        fn listings(
+            filter: royalty_nft::ListingFilter<AccountId, ClassId, Balance>,
+            offset: u32,
+            limit: u32,
+        ) -> Vec<royalty_nft::Listing<AccountId, ClassId, TokenId, Balance>> {
+            LootNFT::listings(filter, offset, limit)
+        }
+
+        fn quote_sale(
+            class_id: ClassId,
+            token_id: TokenId,
+            buyer: Option<AccountId>,
+        ) -> Result<royalty_nft::SaleQuote<AccountId, Balance>, sp_runtime::RuntimeString> {
+            LootNFT::quote_sale(class_id, token_id, buyer.as_ref())
+                .map_err(|e| sp_runtime::RuntimeString::Borrowed(e.into()))
+        }
+
+        fn quote_mint(
+            class_id: ClassId,
+            minter: Option<AccountId>,
+        ) -> Result<royalty_nft::MintQuote<AccountId, Balance>, sp_runtime::RuntimeString> {
+            LootNFT::quote_mint(class_id, minter.as_ref())
+                .map_err(|e| sp_runtime::RuntimeString::Borrowed(e.into()))
+        }
+    }
+
+    impl pallet_pause_rpc_runtime_api::PauseApi<Block> for Runtime {
+        fn paused_pallets() -> Vec<Vec<u8>> {
+            Pause::paused_pallets()
+        }
+
+        fn paused_calls() -> Vec<(Vec<u8>, Vec<u8>)> {
+            Pause::paused_calls()
+        }
+    }
+
     #[cfg(feature = "runtime-benchmarks")]
     impl frame_benchmarking::Benchmark<Block> for Runtime {
         fn dispatch_benchmark(
@@ -0,0 +1,80 @@
+//! The addresses extrinsics may name accounts by, and how the runtime resolves them.
+
+use super::{AccountId, AccountIndex, Identity, Indices};
+use sp_std::prelude::*;
+use codec::{Decode, Encode};
+use sp_runtime::{
+    traits::{LookupError, StaticLookup},
+    RuntimeDebug,
+};
+
+/// An account given by its id, a short index or a username.
+#[derive(Encode, Decode, Clone, Eq, PartialEq, RuntimeDebug)]
+pub enum MultiAddress<AccountId, AccountIndex> {
+    /// The account id itself.
+    Id(AccountId),
+    /// An index claimed through `pallet_indices`.
+    Index(#[codec(compact)] AccountIndex),
+    /// The raw display name of an identity judged `Reasonable` or `KnownGood`, in any case and
+    /// optionally prefixed with `@`. It names whoever holds the name when the extrinsic is
+    /// included, see `pallet_identity::Module::lookup_username`.
+    Username(Vec<u8>),
+}
+
+impl<AccountId, AccountIndex> From<AccountId> for MultiAddress<AccountId, AccountIndex> {
+    fn from(id: AccountId) -> Self {
+        MultiAddress::Id(id)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<AccountId, AccountIndex> std::fmt::Display for MultiAddress<AccountId, AccountIndex>
+where
+    AccountId: std::fmt::Debug,
+    AccountIndex: std::fmt::Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+/// Resolves a `MultiAddress` to the account it names.
+pub struct AccountLookup;
+
+impl StaticLookup for AccountLookup {
+    type Source = MultiAddress<AccountId, AccountIndex>;
+    type Target = AccountId;
+
+    fn lookup(address: Self::Source) -> Result<AccountId, LookupError> {
+        match address {
+            MultiAddress::Id(id) => Ok(id),
+            MultiAddress::Index(index) => Indices::lookup_index(index).ok_or(LookupError),
+            MultiAddress::Username(name) => Identity::lookup_username(&name).ok_or(LookupError),
+        }
+    }
+
+    fn unlookup(id: AccountId) -> Self::Source {
+        MultiAddress::Id(id)
+    }
+}
+
+#[cfg(feature = "std")]
+mod definitions {
+    use super::*;
+    use crate::type_definitions::TypeDefinition;
+    use serde_json::{json, Value};
+
+    impl TypeDefinition for MultiAddress<AccountId, AccountIndex> {
+        const NAME: &'static str = "MultiAddress";
+
+        fn definition() -> Value {
+            json!({
+                "_enum": {
+                    "Id": "AccountId",
+                    "Index": "Compact<AccountIndex>",
+                    "Username": "Bytes",
+                }
+            })
+        }
+    }
+}
@@ -0,0 +1,296 @@
+//! Class and token data Loot stores in `orml_nft`, and the migration from the storage of spec
+//! 20, whose tokens had plain `u32` data and could not be nested.
+
+use super::{AccountId, ClassId, Runtime, TokenId};
+use codec::{Decode, Encode};
+use frame_support::{
+    traits::{Get, OnRuntimeUpgrade},
+    weights::Weight,
+    IterableStorageDoubleMap, IterableStorageMap, StorageValue,
+};
+use orml_nft::{ClassInfo, NftReleases, TokenInfoOf, TokenOwner};
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+use sp_runtime::RuntimeDebug;
+use sp_std::{cell::Cell, prelude::*};
+
+/// How rare a token is.
+#[derive(Encode, Decode, Clone, Copy, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum Rarity {
+    Common,
+    Uncommon,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+impl Default for Rarity {
+    fn default() -> Self {
+        Rarity::Common
+    }
+}
+
+/// Properties shared by every token of a class.
+#[derive(Encode, Decode, Clone, Default, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct ClassData {
+    /// Loot-defined category of the items in the class.
+    pub category: u32,
+    /// Whether a registrar had vouched for the creator's identity when the class was created.
+    /// Always set by the runtime.
+    pub creator_verified: bool,
+}
+
+/// Properties of a single token.
+#[derive(Encode, Decode, Clone, Default, Eq, PartialEq, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "std", serde(rename_all = "camelCase"))]
+pub struct TokenData {
+    pub rarity: Rarity,
+    /// Royalty of the class when the token was minted, in percent. Always set by the runtime.
+    pub royalty: u32,
+}
+
+/// Fills in `creator_verified` and the royalty snapshot, so they can't be forged by callers.
+pub struct LootDataPolicy;
+
+impl royalty_nft::DataPolicy<AccountId, ClassData, TokenData> for LootDataPolicy {
+    fn class_data(owner: &AccountId, data: ClassData) -> ClassData {
+        let creator_verified = pallet_identity::Module::<Runtime>::has_good_judgement(owner);
+
+        ClassData {
+            creator_verified,
+            ..data
+        }
+    }
+
+    fn token_data(royalty: u32, data: TokenData) -> TokenData {
+        TokenData { royalty, ..data }
+    }
+}
+
+/// Token info as stored on spec 20, before tokens could be nested and with `u32` data.
+#[derive(Encode, Decode)]
+struct TokenInfoV1 {
+    metadata: Vec<u8>,
+    owner: AccountId,
+    data: u32,
+}
+
+/// Migrate the `orml_nft` storage of spec 20 to the current layout: wrap token owners in
+/// `TokenOwner::Account` and convert the `u32` class and token data to `ClassData` and
+/// `TokenData`.
+///
+/// The old class data becomes the category. The old token data had no agreed meaning and is
+/// dropped: tokens migrate as common, with the current royalty of their class. This runs before
+/// the upgrades of `AllModules`, so it moves `orml_nft::StorageVersion` to `V2` itself and the
+/// pallet's own owner migration, which knows nothing of the old data, is skipped.
+pub struct MigrateNftData;
+
+impl OnRuntimeUpgrade for MigrateNftData {
+    fn on_runtime_upgrade() -> Weight {
+        if orml_nft::Module::<Runtime>::storage_version() != NftReleases::V1 {
+            return <Runtime as frame_system::Trait>::DbWeight::get().reads(1);
+        }
+
+        let classes = Cell::new(0 as Weight);
+        orml_nft::Classes::<Runtime>::translate::<ClassInfo<TokenId, AccountId, u32>, _>(|_, info| {
+            classes.set(classes.get() + 1);
+            Some(ClassInfo {
+                metadata: info.metadata,
+                total_issuance: info.total_issuance,
+                owner: info.owner,
+                data: ClassData {
+                    category: info.data,
+                    creator_verified: false,
+                },
+            })
+        });
+
+        let tokens = Cell::new(0 as Weight);
+        orml_nft::Tokens::<Runtime>::translate::<TokenInfoV1, _>(|class_id, _, info| {
+            tokens.set(tokens.get() + 1);
+            Some(TokenInfoOf::<Runtime> {
+                metadata: info.metadata,
+                owner: TokenOwner::Account(info.owner),
+                data: TokenData {
+                    rarity: Rarity::Common,
+                    royalty: royalty_nft::Module::<Runtime>::info(class_id)
+                        .map_or(0, |(_, _, royalty)| royalty),
+                },
+            })
+        });
+
+        orml_nft::StorageVersion::put(NftReleases::V2);
+
+        <Runtime as frame_system::Trait>::DbWeight::get().reads_writes(
+            1 + classes.get() + 2 * tokens.get(),
+            1 + classes.get() + tokens.get(),
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+mod definitions {
+    use super::*;
+    use crate::type_definitions::TypeDefinition;
+    use orml_nft::TokenInfo;
+    use serde_json::{json, Value};
+
+    impl TypeDefinition for Rarity {
+        const NAME: &'static str = "Rarity";
+
+        fn definition() -> Value {
+            json!({ "_enum": ["Common", "Uncommon", "Rare", "Epic", "Legendary"] })
+        }
+    }
+
+    impl TypeDefinition for ClassData {
+        const NAME: &'static str = "ClassData";
+
+        fn definition() -> Value {
+            json!({ "category": "u32", "creator_verified": "bool" })
+        }
+    }
+
+    impl TypeDefinition for TokenData {
+        const NAME: &'static str = "TokenData";
+
+        fn definition() -> Value {
+            json!({ "rarity": Rarity::NAME, "royalty": "u32" })
+        }
+    }
+
+    impl TypeDefinition for TokenOwner<AccountId, ClassId, TokenId> {
+        const NAME: &'static str = "TokenOwner";
+
+        fn definition() -> Value {
+            json!({ "_enum": { "Account": "AccountId", "Token": "(ClassId, TokenId)" } })
+        }
+    }
+
+    impl TypeDefinition for ClassInfo<TokenId, AccountId, ClassData> {
+        const NAME: &'static str = "ClassInfo";
+
+        fn definition() -> Value {
+            json!({
+                "metadata": "CID",
+                "total_issuance": "TokenId",
+                "owner": "AccountId",
+                "data": ClassData::NAME,
+            })
+        }
+    }
+
+    impl TypeDefinition for TokenInfo<AccountId, ClassId, TokenId, TokenData> {
+        const NAME: &'static str = "TokenInfo";
+
+        fn definition() -> Value {
+            json!({
+                "metadata": "CID",
+                "owner": TokenOwner::<AccountId, ClassId, TokenId>::NAME,
+                "data": TokenData::NAME,
+            })
+        }
+    }
+
+    impl TypeDefinition for NftReleases {
+        const NAME: &'static str = "NftReleases";
+
+        fn definition() -> Value {
+            json!({ "_enum": ["V1", "V2"] })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use frame_support::{StorageDoubleMap, StorageMap};
+    use orml_nft::TokenInfo;
+
+    type Nft = orml_nft::Module<Runtime>;
+
+    fn alice() -> AccountId {
+        AccountId::from([1; 32])
+    }
+
+    fn new_test_ext() -> sp_io::TestExternalities {
+        frame_system::GenesisConfig::default()
+            .build_storage::<Runtime>()
+            .unwrap()
+            .into()
+    }
+
+    /// Store a class with `data` and a token of it, encoded as on spec 20.
+    fn put_spec_20_token(class_id: ClassId, token_id: TokenId, data: u32) {
+        let class = ClassInfo::<TokenId, AccountId, u32> {
+            metadata: vec![1],
+            total_issuance: 1,
+            owner: alice(),
+            data,
+        };
+        let token = TokenInfoV1 {
+            metadata: vec![2],
+            owner: alice(),
+            data,
+        };
+        frame_support::storage::unhashed::put(&orml_nft::Classes::<Runtime>::hashed_key_for(class_id), &class);
+        frame_support::storage::unhashed::put(
+            &orml_nft::Tokens::<Runtime>::hashed_key_for(class_id, token_id),
+            &token,
+        );
+    }
+
+    #[test]
+    fn spec_20_tokens_survive_the_upgrade() {
+        new_test_ext().execute_with(|| {
+            put_spec_20_token(0, 0, 3);
+            put_spec_20_token(1, 0, 7);
+            royalty_nft::Info::<Runtime>::insert(0, (true, 10, 5));
+            assert_eq!(Nft::storage_version(), NftReleases::V1);
+
+            // as `Executive` does: the custom upgrade first, then those of the pallets
+            MigrateNftData::on_runtime_upgrade();
+            Nft::on_runtime_upgrade();
+
+            assert_eq!(Nft::storage_version(), NftReleases::V2);
+            assert_eq!(
+                Nft::classes(0).map(|class| class.data),
+                Some(ClassData {
+                    category: 3,
+                    creator_verified: false
+                })
+            );
+            assert_eq!(
+                Nft::tokens(0, 0),
+                Some(TokenInfo {
+                    metadata: vec![2],
+                    owner: TokenOwner::Account(alice()),
+                    data: TokenData {
+                        rarity: Rarity::Common,
+                        royalty: 5
+                    },
+                })
+            );
+            assert_eq!(Nft::tokens(1, 0).map(|token| token.data.royalty), Some(0));
+            assert!(Nft::is_owner(&alice(), (1, 0)));
+        });
+    }
+
+    #[test]
+    fn migrated_storage_is_left_alone() {
+        new_test_ext().execute_with(|| {
+            orml_nft::StorageVersion::put(NftReleases::V2);
+            put_spec_20_token(0, 0, 3);
+
+            MigrateNftData::on_runtime_upgrade();
+
+            // not walked again, so the (here bogus) entries stay as they were
+            assert_eq!(Nft::tokens(0, 0), None);
+            assert_eq!(Nft::storage_version(), NftReleases::V2);
+        });
+    }
+}
@@ -0,0 +1,38 @@
+//! What may be paused in an emergency.
+
+use frame_support::traits::Contains;
+use sp_std::prelude::*;
+
+/// Pallets `pallet_pause` refuses to pause: the pause itself, the pallets root and the council
+/// are reached through, those with inherents or the sudo key, and GRANDPA, so equivocation
+/// reports always get through.
+pub struct UnpausablePallets;
+
+impl Contains<Vec<u8>> for UnpausablePallets {
+    fn sorted_members() -> Vec<Vec<u8>> {
+        // the names given in `construct_runtime!`, kept sorted
+        vec![
+            b"Council".to_vec(),
+            b"Democracy".to_vec(),
+            b"Grandpa".to_vec(),
+            b"Pause".to_vec(),
+            b"Scheduler".to_vec(),
+            b"Sudo".to_vec(),
+            b"System".to_vec(),
+            b"TechnicalCommittee".to_vec(),
+            b"Timestamp".to_vec(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unpausable_pallets_are_sorted() {
+        let mut sorted = UnpausablePallets::sorted_members();
+        sorted.sort();
+        assert_eq!(sorted, UnpausablePallets::sorted_members());
+    }
+}
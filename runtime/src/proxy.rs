@@ -0,0 +1,173 @@
+//! What a proxy may do on behalf of the account that added it.
+
+use super::{Call, Runtime};
+use codec::{Decode, Encode};
+use frame_support::traits::InstanceFilter;
+use sp_runtime::RuntimeDebug;
+
+/// The calls a proxy may make, e.g. so a hot wallet can look after the items of a cold wallet
+/// without being able to take anything from it.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Encode, Decode, RuntimeDebug)]
+pub enum ProxyType {
+    /// Any call.
+    Any,
+    /// `royalty_nft` calls that neither pay from the account's balance nor let its items or
+    /// shares leave it, also within `pallet_utility` batches. The proxy can manage classes,
+    /// delist, freeze, nest, burn and redeem, but can't move value to anyone.
+    Nft,
+    /// Every `royalty_nft` call, including transfers, listings and calls paying from the
+    /// account's balance. The proxy is trusted with everything the account holds on the
+    /// marketplace, e.g. it could list the account's items at any price and buy them itself.
+    NftTrading,
+}
+
+impl Default for ProxyType {
+    fn default() -> Self {
+        ProxyType::Any
+    }
+}
+
+impl InstanceFilter<Call> for ProxyType {
+    fn filter(&self, call: &Call) -> bool {
+        match self {
+            ProxyType::Any => true,
+            // batched calls are dispatched with the proxy's filtered origin, so each of them is
+            // checked against this filter as well
+            ProxyType::Nft => match call {
+                Call::LootNFT(call) => !moves_value(call),
+                Call::Utility(..) => true,
+                _ => false,
+            },
+            ProxyType::NftTrading => matches!(call, Call::LootNFT(..) | Call::Utility(..)),
+        }
+    }
+
+    fn is_superset(&self, other: &Self) -> bool {
+        match (self, other) {
+            (x, y) if x == y => true,
+            (ProxyType::Any, _) => true,
+            (ProxyType::NftTrading, ProxyType::Nft) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Whether a `royalty_nft` call pays from the balance of the account dispatching it or lets its
+/// items or shares go to someone else. Listing counts as the latter, since the proxy could set
+/// any price; so does fractionalizing, which sets a buyout price.
+fn moves_value(call: &royalty_nft::Call<Runtime>) -> bool {
+    matches!(
+        call,
+        royalty_nft::Call::buy(..)
+            | royalty_nft::Call::buyout(..)
+            | royalty_nft::Call::mint_nft_token(..)
+            | royalty_nft::Call::nft_transfer(..)
+            | royalty_nft::Call::batch_transfer(..)
+            | royalty_nft::Call::create_sale(..)
+            | royalty_nft::Call::fractionalize(..)
+            | royalty_nft::Call::transfer_shares(..)
+    )
+}
+
+#[cfg(feature = "std")]
+mod definitions {
+    use super::*;
+    use crate::type_definitions::TypeDefinition;
+    use serde_json::{json, Value};
+
+    impl TypeDefinition for ProxyType {
+        const NAME: &'static str = "ProxyType";
+
+        fn definition() -> Value {
+            json!({ "_enum": ["Any", "Nft", "NftTrading"] })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AccountId, BalancesCall, MultiAddress, Runtime};
+    use royalty_nft::Call as NftCall;
+
+    fn dest() -> MultiAddress<AccountId, crate::AccountIndex> {
+        MultiAddress::Id(AccountId::from([1; 32]))
+    }
+
+    fn loot_calls(calls: Vec<NftCall<Runtime>>) -> Vec<Call> {
+        calls.into_iter().map(Call::LootNFT).collect()
+    }
+
+    /// Calls that don't move value out of the proxied account.
+    fn nft_calls() -> Vec<Call> {
+        loot_calls(vec![
+            NftCall::create_nft_class(vec![0], Default::default(), 10, 10, false),
+            NftCall::delete_sale(0, 0),
+            NftCall::nest_nft(0, 1, 0, 0),
+            NftCall::burn(0, 0),
+            NftCall::freeze_token(0, 0),
+            NftCall::redeem(0, 0),
+        ])
+    }
+
+    fn value_moving_calls() -> Vec<Call> {
+        loot_calls(vec![
+            NftCall::mint_nft_token(0, vec![0], Default::default()),
+            NftCall::buy(0, 0),
+            NftCall::buyout(0, 0),
+            NftCall::nft_transfer(dest(), 0, 0),
+            NftCall::batch_transfer(vec![(dest(), 0, 0), (dest(), 0, 1)]),
+            NftCall::create_sale(0, 0, 1),
+            NftCall::fractionalize(0, 0, 10, 1),
+            NftCall::transfer_shares(dest(), 0, 0, 5),
+        ])
+    }
+
+    fn balance_transfer() -> Call {
+        Call::Balances(BalancesCall::transfer(dest(), 10))
+    }
+
+    #[test]
+    fn nft_proxy_allows_royalty_nft_calls_only() {
+        for call in nft_calls() {
+            assert!(ProxyType::Nft.filter(&call), "{:?}", call);
+            assert!(ProxyType::NftTrading.filter(&call), "{:?}", call);
+            assert!(ProxyType::Any.filter(&call), "{:?}", call);
+        }
+
+        for proxy_type in &[ProxyType::Nft, ProxyType::NftTrading] {
+            assert!(!proxy_type.filter(&balance_transfer()));
+            assert!(!proxy_type.filter(&Call::Identity(pallet_identity::Call::clear_identity())));
+        }
+        assert!(ProxyType::Any.filter(&balance_transfer()));
+    }
+
+    #[test]
+    fn only_trading_proxy_moves_value() {
+        // e.g. a hot wallet listing an item of the cold wallet for next to nothing and buying it,
+        // or simply sending it away
+        for call in value_moving_calls() {
+            assert!(!ProxyType::Nft.filter(&call), "{:?}", call);
+            assert!(ProxyType::NftTrading.filter(&call), "{:?}", call);
+            assert!(ProxyType::Any.filter(&call), "{:?}", call);
+        }
+    }
+
+    #[test]
+    fn nft_proxy_allows_batches() {
+        // the batch itself passes; its calls are filtered one by one when dispatched
+        let batch = Call::Utility(pallet_utility::Call::batch_all(nft_calls()));
+        assert!(ProxyType::Nft.filter(&batch));
+    }
+
+    #[test]
+    fn any_is_superset_of_nft() {
+        assert!(ProxyType::Any.is_superset(&ProxyType::Nft));
+        assert!(ProxyType::Any.is_superset(&ProxyType::NftTrading));
+        assert!(ProxyType::NftTrading.is_superset(&ProxyType::Nft));
+        assert!(ProxyType::Nft.is_superset(&ProxyType::Nft));
+        assert!(!ProxyType::Nft.is_superset(&ProxyType::NftTrading));
+        assert!(!ProxyType::Nft.is_superset(&ProxyType::Any));
+        assert!(!ProxyType::NftTrading.is_superset(&ProxyType::Any));
+    }
+}
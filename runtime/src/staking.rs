@@ -0,0 +1,215 @@
+//! Helpers for nominated proof-of-stake: how stake is turned into votes, how much is paid out
+//! as staking rewards, and the validators of chains upgraded from spec 20.
+
+use super::{
+    opaque::SessionKeys, AccountId, Aura, Balance, Balances, Grandpa, MaximumBlockWeight,
+    MultiAddress, Origin, Runtime, Session, Staking,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+    parameter_types,
+    storage::migration::put_storage_value,
+    traits::{Get, OnRuntimeUpgrade},
+    weights::Weight,
+    StorageHasher, StorageValue, Twox64Concat,
+};
+use pallet_staking::{RewardDestination, ValidatorPrefs};
+use sp_runtime::{
+    curve::PiecewiseLinear,
+    traits::{Convert, OpaqueKeys},
+};
+use sp_std::prelude::*;
+
+// Yearly inflation paid out to validators and nominators, depending on the share of the issuance
+// that is staked: from 2.5% up to 10% at the ideal stake of 50%.
+pallet_staking_reward_curve::build! {
+    const REWARD_CURVE: PiecewiseLinear<'static> = curve!(
+        min_inflation: 0_025_000,
+        max_inflation: 0_100_000,
+        ideal_stake: 0_500_000,
+        falloff: 0_050_000,
+        max_piece_count: 40,
+        test_precision: 0_005_000,
+    );
+}
+
+parameter_types! {
+    pub const RewardCurve: &'static PiecewiseLinear<'static> = &REWARD_CURVE;
+}
+
+/// Scales balances down to the `u64` votes of the election, and election results back up.
+pub struct CurrencyToVoteHandler;
+
+impl CurrencyToVoteHandler {
+    fn factor() -> Balance {
+        (Balances::total_issuance() / u64::max_value() as Balance).max(1)
+    }
+}
+
+impl Convert<Balance, u64> for CurrencyToVoteHandler {
+    fn convert(x: Balance) -> u64 {
+        (x / Self::factor()) as u64
+    }
+}
+
+impl Convert<u128, Balance> for CurrencyToVoteHandler {
+    fn convert(x: u128) -> Balance {
+        x * Self::factor()
+    }
+}
+
+/// Seed `pallet_session` and `pallet_staking` on chains upgraded from spec 20, which had fixed
+/// Aura and GRANDPA authorities and neither pallet. Without validators and their keys, the first
+/// session rotation would hand Aura and GRANDPA an empty set and stall the chain.
+///
+/// Each Aura authority becomes a validator whose stash and controller is the account of its Aura
+/// key, with the GRANDPA authority at the same index for its session keys. Half of its free
+/// balance is bonded, leaving the rest for fees; validators that can't bond still keep producing
+/// blocks, as staking keeps the current set while too few candidates are staked.
+pub struct SeedValidators;
+
+impl OnRuntimeUpgrade for SeedValidators {
+    fn on_runtime_upgrade() -> Weight {
+        if !Session::validators().is_empty() {
+            return <Runtime as frame_system::Trait>::DbWeight::get().reads(1);
+        }
+
+        let validators: Vec<(AccountId, SessionKeys)> = Aura::authorities()
+            .into_iter()
+            .zip(Grandpa::grandpa_authorities())
+            .filter_map(|(aura, (grandpa, _))| {
+                // an sr25519 key and its account share their 32 bytes
+                let account = AccountId::decode(&mut &aura.encode()[..]).ok()?;
+                Some((account, SessionKeys { aura, grandpa }))
+            })
+            .collect();
+
+        // as `pallet_session` stores keys set by `set_keys`, whose storage it keeps private
+        for (validator, keys) in &validators {
+            frame_system::Module::<Runtime>::inc_ref(validator);
+            let hash = Twox64Concat::hash(&validator.encode());
+            put_storage_value(b"Session", b"NextKeys", &hash, keys);
+            for id in SessionKeys::key_ids() {
+                let hash = Twox64Concat::hash(&(*id, keys.get_raw(*id).to_vec()).encode());
+                put_storage_value(b"Session", b"KeyOwner", &hash, validator);
+            }
+        }
+        let stashes: Vec<AccountId> = validators.iter().map(|(stash, _)| stash.clone()).collect();
+        pallet_session::Validators::<Runtime>::put(&stashes);
+        pallet_session::QueuedKeys::<Runtime>::put(&validators);
+
+        let stakers: Vec<AccountId> = stashes
+            .into_iter()
+            .filter(|stash| {
+                let bond = Balances::free_balance(stash) / 2;
+                Staking::bond(
+                    Origin::signed(stash.clone()),
+                    MultiAddress::Id(stash.clone()),
+                    bond,
+                    RewardDestination::Staked,
+                )
+                .and_then(|_| {
+                    Staking::validate(Origin::signed(stash.clone()), ValidatorPrefs::default())
+                })
+                .is_ok()
+            })
+            .collect();
+        // as the genesis of new networks configures staking
+        pallet_staking::ValidatorCount::put(stakers.len() as u32 * 2);
+        pallet_staking::MinimumValidatorCount::put(stakers.len() as u32);
+        pallet_staking::Invulnerables::<Runtime>::put(stakers);
+
+        // a one-off, allowed to fill its block
+        MaximumBlockWeight::get()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AuraId, GrandpaId, DOTS};
+    use frame_support::StorageMap;
+    use sp_core::{ed25519, sr25519};
+
+    fn validator(seed: u8) -> (AccountId, SessionKeys) {
+        let keys = SessionKeys {
+            aura: AuraId::from(sr25519::Public::from_raw([seed; 32])),
+            grandpa: GrandpaId::from(ed25519::Public::from_raw([seed; 32])),
+        };
+        (AccountId::from([seed; 32]), keys)
+    }
+
+    /// Storage of a spec 20 chain run by `validators`, with Aura and GRANDPA only.
+    fn spec_20_ext(validators: &[(AccountId, SessionKeys)]) -> sp_io::TestExternalities {
+        let mut t = frame_system::GenesisConfig::default()
+            .build_storage::<Runtime>()
+            .unwrap();
+        pallet_balances::GenesisConfig::<Runtime> {
+            balances: validators
+                .iter()
+                .map(|(account, _)| (account.clone(), 1_000 * DOTS))
+                .collect(),
+        }
+        .assimilate_storage(&mut t)
+        .unwrap();
+        pallet_aura::GenesisConfig::<Runtime> {
+            authorities: validators.iter().map(|(_, keys)| keys.aura.clone()).collect(),
+        }
+        .assimilate_storage(&mut t)
+        .unwrap();
+        pallet_grandpa::GenesisConfig {
+            authorities: validators.iter().map(|(_, keys)| (keys.grandpa.clone(), 1)).collect(),
+        }
+        .assimilate_storage::<Runtime>(&mut t)
+        .unwrap();
+        t.into()
+    }
+
+    #[test]
+    fn spec_20_validators_are_seeded() {
+        let validators = vec![validator(1), validator(2)];
+        let stashes: Vec<_> = validators.iter().map(|(stash, _)| stash.clone()).collect();
+        let aura: Vec<_> = validators.iter().map(|(_, keys)| keys.aura.clone()).collect();
+
+        spec_20_ext(&validators).execute_with(|| {
+            SeedValidators::on_runtime_upgrade();
+
+            assert_eq!(Session::validators(), stashes);
+            assert_eq!(Session::queued_keys(), validators);
+            for stash in &stashes {
+                assert_eq!(Staking::bonded(stash), Some(stash.clone()));
+                assert_eq!(Staking::ledger(stash).map(|ledger| ledger.active), Some(500 * DOTS));
+                assert!(pallet_staking::Validators::<Runtime>::contains_key(stash));
+            }
+            assert_eq!(Staking::minimum_validator_count(), 2);
+
+            // the keys stay with the validators through the rotations that start staking
+            Session::rotate_session();
+            Session::rotate_session();
+            assert_eq!(Staking::current_era(), Some(0));
+
+            let mut validators_now = Session::validators();
+            validators_now.sort();
+            assert_eq!(validators_now, stashes);
+            let mut queued = Session::queued_keys();
+            queued.sort_by(|a, b| a.0.cmp(&b.0));
+            assert_eq!(queued, validators);
+            let mut authorities = Aura::authorities();
+            authorities.sort();
+            assert_eq!(authorities, aura);
+        });
+    }
+
+    #[test]
+    fn sessions_already_running_are_left_alone() {
+        let validators = vec![validator(1)];
+        spec_20_ext(&validators).execute_with(|| {
+            pallet_session::Validators::<Runtime>::put(vec![validators[0].0.clone()]);
+
+            SeedValidators::on_runtime_upgrade();
+
+            assert!(Session::queued_keys().is_empty());
+            assert_eq!(Staking::bonded(&validators[0].0), None);
+        });
+    }
+}
@@ -0,0 +1,47 @@
+//! How the treasury is funded from transaction fees, and who may tip from it.
+
+use super::{AccountId, Balances, Council, CouncilMaxMembers, Treasury};
+use frame_support::traits::{Contains, ContainsLengthBound, Currency, Get, Imbalance, OnUnbalanced};
+use sp_std::prelude::*;
+
+type NegativeImbalance = <Balances as Currency<AccountId>>::NegativeImbalance;
+
+/// Percentage of transaction fees and tips paid into the treasury. The rest is burned.
+pub const TREASURY_FEE_SHARE: u32 = 20;
+
+/// Pays `TREASURY_FEE_SHARE` of every transaction fee and tip into the treasury.
+pub struct DealWithFees;
+
+impl OnUnbalanced<NegativeImbalance> for DealWithFees {
+    fn on_unbalanceds<B>(mut fees_then_tips: impl Iterator<Item = NegativeImbalance>) {
+        if let Some(fees) = fees_then_tips.next() {
+            let mut split = fees.ration(TREASURY_FEE_SHARE, 100 - TREASURY_FEE_SHARE);
+            if let Some(tips) = fees_then_tips.next() {
+                tips.ration_merge_into(TREASURY_FEE_SHARE, 100 - TREASURY_FEE_SHARE, &mut split);
+            }
+
+            // the second part is burned, as dropping an imbalance reduces the total issuance
+            Treasury::on_unbalanced(split.0);
+        }
+    }
+}
+
+/// The council members, who decide on treasury tips.
+pub struct CouncilTippers;
+
+impl Contains<AccountId> for CouncilTippers {
+    fn sorted_members() -> Vec<AccountId> {
+        // the collective keeps its members sorted
+        Council::members()
+    }
+}
+
+impl ContainsLengthBound for CouncilTippers {
+    fn min_len() -> usize {
+        0
+    }
+
+    fn max_len() -> usize {
+        CouncilMaxMembers::get() as usize
+    }
+}
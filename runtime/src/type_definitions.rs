@@ -0,0 +1,392 @@
+//! Type definitions for polkadot.js, kept next to the runtime types so `type.json` can't drift
+//! from them. `loot-node export-types` writes them out after checking that every type in the
+//! runtime metadata is covered.
+
+use crate::lookup::MultiAddress;
+use crate::nft::{ClassData, Rarity, TokenData};
+use crate::proxy::ProxyType;
+use crate::{AccountId, AccountIndex, Balance, ClassId, TokenId};
+use orml_nft::{ClassInfo, NftReleases, TokenInfo, TokenOwner};
+use pallet_identity::{IdentityInfo, IdentityReleases, Judgement, RegistrarInfo, Registration};
+use royalty_nft::{ClassDetails, Fraction, FrozenBy, Listing, ListingFilter, MintQuote, SaleQuote};
+use serde_json::{json, Map, Value};
+
+/// A type with a custom polkadot.js definition.
+pub trait TypeDefinition {
+    /// Name the type is registered under.
+    const NAME: &'static str;
+
+    /// Definition of the type: a type name, or an object describing a struct or an `_enum`.
+    fn definition() -> Value;
+}
+
+fn add<T: TypeDefinition>(types: &mut Map<String, Value>) {
+    types.insert(T::NAME.into(), T::definition());
+}
+
+impl TypeDefinition for Listing<AccountId, ClassId, TokenId, Balance> {
+    const NAME: &'static str = "Listing";
+
+    fn definition() -> Value {
+        json!({
+            "class_id": "ClassId",
+            "token_id": "TokenId",
+            "seller": "AccountId",
+            "price": "Balance",
+            "royalty": "Balance",
+        })
+    }
+}
+
+impl TypeDefinition for ListingFilter<AccountId, ClassId, Balance> {
+    const NAME: &'static str = "ListingFilter";
+
+    fn definition() -> Value {
+        json!({
+            "class_id": "Option<ClassId>",
+            "seller": "Option<AccountId>",
+            "min_price": "Option<Balance>",
+            "max_price": "Option<Balance>",
+        })
+    }
+}
+
+impl TypeDefinition for ClassDetails<AccountId, Balance> {
+    const NAME: &'static str = "ClassDetails";
+
+    fn definition() -> Value {
+        json!({
+            "owner": "AccountId",
+            "can_mint": "bool",
+            "mint_price": "Balance",
+            "royalty": "u32",
+            "soulbound": "bool",
+        })
+    }
+}
+
+impl TypeDefinition for SaleQuote<AccountId, Balance> {
+    const NAME: &'static str = "SaleQuote";
+
+    fn definition() -> Value {
+        json!({
+            "seller": "AccountId",
+            "royalty_recipient": "AccountId",
+            "price": "Balance",
+            "royalty": "Balance",
+            "platform_fee": "Balance",
+            "seller_receives": "Balance",
+        })
+    }
+}
+
+impl TypeDefinition for MintQuote<AccountId, Balance> {
+    const NAME: &'static str = "MintQuote";
+
+    fn definition() -> Value {
+        json!({ "class_owner": "AccountId", "price": "Balance" })
+    }
+}
+
+impl TypeDefinition for Fraction<AccountId, Balance> {
+    const NAME: &'static str = "Fraction";
+
+    fn definition() -> Value {
+        json!({
+            "issuer": "AccountId",
+            "total_shares": "u64",
+            "holders": "u32",
+            "buyout_price": "Balance",
+        })
+    }
+}
+
+impl TypeDefinition for FrozenBy {
+    const NAME: &'static str = "FrozenBy";
+
+    fn definition() -> Value {
+        json!({ "_enum": ["ClassOwner", "FreezeOrigin"] })
+    }
+}
+
+impl TypeDefinition for IdentityInfo {
+    const NAME: &'static str = "IdentityInfo";
+
+    fn definition() -> Value {
+        json!({
+            "additional": "Vec<(Data, Data)>",
+            "display": "Data",
+            "legal": "Data",
+            "web": "Data",
+            "riot": "Data",
+            "email": "Data",
+            "pgp_fingerprint": "Option<[u8; 20]>",
+            "image": "Data",
+            "twitter": "Data",
+        })
+    }
+}
+
+impl TypeDefinition for Judgement<Balance> {
+    const NAME: &'static str = "Judgement";
+
+    fn definition() -> Value {
+        json!({
+            "_enum": {
+                "Unknown": "Null",
+                "FeePaid": "Balance",
+                "Reasonable": "Null",
+                "KnownGood": "Null",
+                "OutOfDate": "Null",
+                "LowQuality": "Null",
+                "Erroneous": "Null",
+            }
+        })
+    }
+}
+
+impl TypeDefinition for Registration<Balance> {
+    const NAME: &'static str = "Registration";
+
+    fn definition() -> Value {
+        json!({
+            "judgements": "Vec<(RegistrarIndex, Judgement)>",
+            "deposit": "Balance",
+            "info": IdentityInfo::NAME,
+        })
+    }
+}
+
+impl TypeDefinition for RegistrarInfo<Balance, AccountId> {
+    const NAME: &'static str = "RegistrarInfo";
+
+    fn definition() -> Value {
+        json!({ "account": "AccountId", "fee": "Balance", "fields": "IdentityFields" })
+    }
+}
+
+impl TypeDefinition for IdentityReleases {
+    const NAME: &'static str = "IdentityReleases";
+
+    fn definition() -> Value {
+        json!({ "_enum": ["V1", "V2"] })
+    }
+}
+
+/// Every custom type of the Loot runtime, as in `type.json`.
+pub fn type_definitions() -> Value {
+    let mut types = json!({
+        "CurrencyId": "u32",
+        "CurrencyIdOf": "CurrencyId",
+        "Address": "MultiAddress",
+        "LookupSource": "MultiAddress",
+        "CID": "Vec<u8>",
+        "ClassId": "u64",
+        "ClassIdOf": "ClassId",
+        "TokenId": "u64",
+        "TokenIdOf": "TokenId",
+        "ClassDataOf": "ClassData",
+        "TokenDataOf": "TokenData",
+        "ClassInfoOf": "ClassInfo",
+        "TokenInfoOf": "TokenInfo",
+        "FractionOf": "Fraction",
+        "RegistrarIndex": "u32",
+        "IdentityFields": "u64",
+        "PalletsOrigin": "OriginCaller",
+        "OriginCaller": {
+            "_enum": {
+                "System": "SystemOrigin",
+                "Council": "CollectiveOrigin",
+                "TechnicalCommittee": "CollectiveOrigin",
+            }
+        },
+        "Keys": "SessionKeys2",
+    });
+
+    let map = types.as_object_mut().expect("a JSON object was built above; qed");
+    add::<Rarity>(map);
+    add::<ClassData>(map);
+    add::<TokenData>(map);
+    add::<TokenOwner<AccountId, ClassId, TokenId>>(map);
+    add::<ClassInfo<TokenId, AccountId, ClassData>>(map);
+    add::<TokenInfo<AccountId, ClassId, TokenId, TokenData>>(map);
+    add::<NftReleases>(map);
+    add::<Listing<AccountId, ClassId, TokenId, Balance>>(map);
+    add::<ListingFilter<AccountId, ClassId, Balance>>(map);
+    add::<ClassDetails<AccountId, Balance>>(map);
+    add::<SaleQuote<AccountId, Balance>>(map);
+    add::<MintQuote<AccountId, Balance>>(map);
+    add::<Fraction<AccountId, Balance>>(map);
+    add::<FrozenBy>(map);
+    add::<IdentityInfo>(map);
+    add::<Judgement<Balance>>(map);
+    add::<Registration<Balance>>(map);
+    add::<RegistrarInfo<Balance, AccountId>>(map);
+    add::<IdentityReleases>(map);
+    add::<MultiAddress<AccountId, AccountIndex>>(map);
+    add::<ProxyType>(map);
+
+    types
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codec::{Compact, Decode, Encode};
+    use pallet_identity::{Data, IdentityFields};
+
+    fn account(seed: u8) -> AccountId {
+        AccountId::from([seed; 32])
+    }
+
+    fn raw(seed: u8) -> Data {
+        Data::Raw(vec![seed])
+    }
+
+    /// SCALE encoding of a value of the polkadot.js type `ty` built from `seed`: the `n`th field
+    /// of a struct holds `n + 1`, an enum is its variant `seed` modulo the number of variants,
+    /// and anything else holds `seed` itself.
+    fn encode_as(types: &Map<String, Value>, ty: &str, seed: u8) -> Vec<u8> {
+        if let Some(inner) = ty.strip_prefix("Option<").and_then(|ty| ty.strip_suffix('>')) {
+            return [vec![1], encode_as(types, inner, seed)].concat();
+        }
+        if let Some(inner) = ty.strip_prefix("Vec<").and_then(|ty| ty.strip_suffix('>')) {
+            return [Compact(1u32).encode(), encode_as(types, inner, seed)].concat();
+        }
+        if let Some(inner) = ty.strip_prefix('(').and_then(|ty| ty.strip_suffix(')')) {
+            return inner.split(", ").flat_map(|ty| encode_as(types, ty, seed)).collect();
+        }
+
+        match ty {
+            "Null" => vec![],
+            "bool" => (seed % 2 == 1).encode(),
+            "u32" | "RegistrarIndex" => u32::from(seed).encode(),
+            "u64" | "ClassId" | "TokenId" | "IdentityFields" => u64::from(seed).encode(),
+            "Balance" => Balance::from(seed).encode(),
+            "AccountId" => account(seed).encode(),
+            "CID" => vec![seed].encode(),
+            "Data" => raw(seed).encode(),
+            "[u8; 20]" => [seed; 20].encode(),
+            _ => match types.get(ty) {
+                Some(Value::String(alias)) => encode_as(types, alias, seed),
+                Some(Value::Object(fields)) => match fields.get("_enum") {
+                    Some(Value::Array(variants)) => vec![seed % variants.len() as u8],
+                    Some(Value::Object(variants)) => {
+                        let index = seed as usize % variants.len();
+                        let variant = variants.values().nth(index).and_then(Value::as_str).unwrap();
+                        [vec![index as u8], encode_as(types, variant, seed)].concat()
+                    }
+                    _ => fields
+                        .values()
+                        .enumerate()
+                        .flat_map(|(n, field)| encode_as(types, field.as_str().unwrap(), n as u8 + 1))
+                        .collect(),
+                },
+                _ => panic!("no test encoding for {}", ty),
+            },
+        }
+    }
+
+    fn assert_defined_as_encoded<T: TypeDefinition + Encode>(value: T) {
+        let types = type_definitions();
+        assert_eq!(
+            value.encode(),
+            encode_as(types.as_object().unwrap(), T::NAME, 0),
+            "the definition of {} doesn't match its SCALE encoding",
+            T::NAME
+        );
+    }
+
+    #[test]
+    fn structs_are_defined_as_encoded() {
+        assert_defined_as_encoded::<Listing<AccountId, ClassId, TokenId, Balance>>(Listing {
+            class_id: 1,
+            token_id: 2,
+            seller: account(3),
+            price: 4,
+            royalty: 5,
+        });
+        assert_defined_as_encoded::<ListingFilter<AccountId, ClassId, Balance>>(ListingFilter {
+            class_id: Some(1),
+            seller: Some(account(2)),
+            min_price: Some(3),
+            max_price: Some(4),
+        });
+        assert_defined_as_encoded::<ClassDetails<AccountId, Balance>>(ClassDetails {
+            owner: account(1),
+            can_mint: false,
+            mint_price: 3,
+            royalty: 4,
+            soulbound: true,
+        });
+        assert_defined_as_encoded::<SaleQuote<AccountId, Balance>>(SaleQuote {
+            seller: account(1),
+            royalty_recipient: account(2),
+            price: 3,
+            royalty: 4,
+            platform_fee: 5,
+            seller_receives: 6,
+        });
+        assert_defined_as_encoded::<MintQuote<AccountId, Balance>>(MintQuote {
+            class_owner: account(1),
+            price: 2,
+        });
+        assert_defined_as_encoded::<Fraction<AccountId, Balance>>(Fraction {
+            issuer: account(1),
+            total_shares: 2,
+            holders: 3,
+            buyout_price: 4,
+        });
+
+        let class_data = ClassData { category: 1, creator_verified: false };
+        let token_data = TokenData { rarity: Rarity::Uncommon, royalty: 2 };
+        assert_defined_as_encoded(class_data.clone());
+        assert_defined_as_encoded(token_data.clone());
+        assert_defined_as_encoded::<ClassInfo<TokenId, AccountId, ClassData>>(ClassInfo {
+            metadata: vec![1],
+            total_issuance: 2,
+            owner: account(3),
+            data: class_data,
+        });
+        assert_defined_as_encoded::<TokenInfo<AccountId, ClassId, TokenId, TokenData>>(TokenInfo {
+            metadata: vec![1],
+            owner: TokenOwner::Account(account(2)),
+            data: token_data,
+        });
+
+        let info = IdentityInfo {
+            additional: vec![(raw(1), raw(1))],
+            display: raw(2),
+            legal: raw(3),
+            web: raw(4),
+            riot: raw(5),
+            email: raw(6),
+            pgp_fingerprint: Some([7; 20]),
+            image: raw(8),
+            twitter: raw(9),
+        };
+        assert_defined_as_encoded(info.clone());
+        assert_defined_as_encoded::<Registration<Balance>>(Registration {
+            judgements: vec![(1, Judgement::FeePaid(1))],
+            deposit: 2,
+            info,
+        });
+        assert_defined_as_encoded::<RegistrarInfo<Balance, AccountId>>(RegistrarInfo {
+            account: account(1),
+            fee: 2,
+            fields: IdentityFields::decode(&mut &3u64.encode()[..]).unwrap(),
+        });
+    }
+
+    #[test]
+    fn type_json_is_up_to_date() {
+        // compared as text, as the order of enum variants matters to polkadot.js
+        let generated = serde_json::to_string_pretty(&type_definitions()).unwrap() + "\n";
+
+        assert!(
+            include_str!("../../type.json") == generated,
+            "type.json is out of date, regenerate it with `loot-node export-types -o type.json`:\n{}",
+            generated
+        );
+    }
+}